@@ -0,0 +1,96 @@
+//! How quickly times are parsed, and how much memory is allocated doing it, for programs that
+//! parse a lot of them. Run with `cargo bench`
+
+use alrm::{opinionated_time_parsing, parse_target};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The system allocator, keeping count of every allocation
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Times of day written the ways people usually write them
+const TIMES: [&str; 12] = [
+    "9",
+    "21",
+    "9pm",
+    "9:30 pm",
+    "9:30 p.m.",
+    "21:30:15",
+    "0900",
+    "6.5",
+    "noon",
+    "pm 6:30",
+    "12 a.m.",
+    "quarter past 6 pm",
+];
+
+/// Everything else that can be counted down to
+const TARGETS: [&str; 8] = [
+    "9:30pm",
+    "1h30m",
+    "PT45M",
+    "+10",
+    "tomorrow 9am",
+    "2024-12-25 9am",
+    "Dec 25 9am",
+    "@1700000000",
+];
+
+/// How many allocations `f` makes each time it's called, on average over `strings`
+fn allocations(strings: &[&str], f: impl Fn(&str)) -> f64 {
+    // the regexes are compiled the first time they're used, which shouldn't count
+    strings.iter().for_each(|s| f(s));
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    strings.iter().for_each(|s| f(s));
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / strings.len() as f64
+}
+
+fn parsing(c: &mut Criterion) {
+    println!(
+        "opinionated_time_parsing: {:.2} allocations per time",
+        allocations(&TIMES, |s| {
+            black_box(opinionated_time_parsing(s).unwrap());
+        })
+    );
+    println!(
+        "parse_target: {:.2} allocations per target",
+        allocations(&TARGETS, |s| {
+            black_box(parse_target(s).unwrap());
+        })
+    );
+
+    c.bench_function("opinionated_time_parsing", |b| {
+        b.iter(|| {
+            for s in TIMES {
+                black_box(opinionated_time_parsing(black_box(s)).unwrap());
+            }
+        })
+    });
+    c.bench_function("parse_target", |b| {
+        b.iter(|| {
+            for s in TARGETS {
+                black_box(parse_target(black_box(s)).unwrap());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, parsing);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Duration, Local};
+
+/**
+ * Counts down to a target, giving how much time is left every time it's asked
+ *
+ * The countdown ends once the target has passed. A target that is exactly now still gives a
+ * zero, so the last thing a countdown yields is as close to zero as it was asked.
+ * This doesn't wait between items, so a display would usually sleep in between each one
+ */
+pub struct Countdown<C = fn() -> DateTime<Local>> {
+    target: DateTime<Local>,
+    clock: C,
+}
+
+/// Count down to `target` on the system clock
+pub fn countdown(target: DateTime<Local>) -> Countdown {
+    Countdown::with_clock(target, Local::now)
+}
+
+impl<C: FnMut() -> DateTime<Local>> Countdown<C> {
+    /// Count down to `target`, asking `clock` what time it is instead of the system clock
+    pub fn with_clock(target: DateTime<Local>, clock: C) -> Self {
+        Countdown { target, clock }
+    }
+
+    /// The time being counted down to
+    pub fn target(&self) -> DateTime<Local> {
+        self.target
+    }
+}
+
+impl<C: FnMut() -> DateTime<Local>> Iterator for Countdown<C> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let remaining = self.target - (self.clock)();
+        if remaining < Duration::zero() {
+            None
+        } else {
+            Some(remaining)
+        }
+    }
+}
+
+#[test]
+fn countdown_with_clock() {
+    use chrono::TimeZone;
+    use std::cell::Cell;
+
+    let start = Local.ymd(2024, 3, 1).and_hms(9, 0, 0);
+    let now = Cell::new(start);
+    let tick = || {
+        let time = now.get();
+        now.set(time + Duration::seconds(1));
+        time
+    };
+    let remaining: Vec<_> = Countdown::with_clock(start + Duration::seconds(3), tick).collect();
+    assert_eq!(
+        remaining,
+        vec![
+            Duration::seconds(3),
+            Duration::seconds(2),
+            Duration::seconds(1),
+            Duration::zero(),
+        ]
+    );
+
+    let mut passed = Countdown::with_clock(start - Duration::seconds(1), || start);
+    assert_eq!(passed.target(), start - Duration::seconds(1));
+    assert_eq!(passed.next(), None);
+}
+
+#[test]
+fn countdown_clock_jumps() {
+    use chrono::TimeZone;
+    use std::cell::RefCell;
+
+    let start = Local.ymd(2024, 3, 1).and_hms(9, 0, 0);
+    // the clock is set back an hour partway through, and then forward past the target
+    let times = RefCell::new(
+        vec![
+            start,
+            start + Duration::seconds(1),
+            start - Duration::hours(1),
+            start + Duration::hours(1),
+        ]
+        .into_iter(),
+    );
+    let clock = || times.borrow_mut().next().unwrap();
+    let remaining: Vec<_> = Countdown::with_clock(start + Duration::seconds(30), clock).collect();
+    assert_eq!(
+        remaining,
+        vec![
+            Duration::seconds(30),
+            Duration::seconds(29),
+            Duration::hours(1) + Duration::seconds(30),
+        ]
+    );
+}
@@ -0,0 +1,140 @@
+//! Drawing the countdown in big digits in the middle of an otherwise empty terminal, for `--fullscreen`
+
+use console::{measure_text_width, Style, Term};
+use std::io;
+
+/// How tall the big digits are, in lines
+const GLYPH_HEIGHT: usize = 5;
+
+/// The shape of each character that can be drawn big, with `#` for a filled block
+fn glyph(c: char) -> Option<[&'static str; GLYPH_HEIGHT]> {
+    Some(match c {
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => [" # ", "## ", " # ", " # ", "###"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", "  #", "  #", "  #"],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        ':' => [" ", "#", " ", "#", " "],
+        'd' => ["  #", "  #", "###", "# #", "###"],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        ' ' => [" ", " ", " ", " ", " "],
+        _ => return None,
+    })
+}
+
+/// Spell out `text` in big block letters, one string per line, or `None` if there's a character
+/// that doesn't have a big version. Every block is two columns wide so the digits come out
+/// roughly square
+fn big_text(text: &str) -> Option<Vec<String>> {
+    let glyphs = text.chars().map(glyph).collect::<Option<Vec<_>>>()?;
+    Some(
+        (0..GLYPH_HEIGHT)
+            .map(|row| {
+                glyphs
+                    .iter()
+                    .map(|glyph| glyph[row].replace('#', "██").replace(' ', "  "))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+            })
+            .collect(),
+    )
+}
+
+/// Lay out `countdown` in big digits with `caption` underneath, centered in a terminal that is
+/// `columns` wide and `rows` tall. If the big digits don't fit, the countdown is shown as-is
+fn frame(countdown: &str, style: &Style, caption: &str, columns: usize, rows: usize) -> String {
+    let lines = match big_text(countdown) {
+        Some(lines) if measure_text_width(&lines[0]) <= columns && GLYPH_HEIGHT + 2 <= rows => {
+            lines
+        }
+        _ => vec![countdown.to_string()],
+    };
+    let centered = |line: &str, styled: String| {
+        let indent = columns.saturating_sub(measure_text_width(line)) / 2;
+        format!("{}{}", " ".repeat(indent), styled)
+    };
+    let mut output: Vec<_> = lines
+        .iter()
+        .map(|line| centered(line, style.apply_to(line).to_string()))
+        .collect();
+    output.push(String::new());
+    output.push(centered(caption, caption.to_string()));
+
+    let top = rows.saturating_sub(output.len()) / 2;
+    format!("{}{}", "\r\n".repeat(top), output.join("\r\n"))
+}
+
+/// The whole terminal, switched over to a blank screen of its own until this is dropped, at
+/// which point whatever was there before comes back
+pub struct Screen<'a> {
+    term: &'a Term,
+}
+
+impl<'a> Screen<'a> {
+    /// Switch `term` over to the alternate screen, where the countdown can take up the whole
+    /// terminal without scrolling away what was there
+    pub fn enter(term: &'a Term) -> io::Result<Self> {
+        term.write_str("\x1b[?1049h")?;
+        term.hide_cursor()?;
+        Ok(Screen { term })
+    }
+
+    /// Redraw the screen with `countdown` in the middle and `caption` under it, in a single
+    /// write so that it never flickers
+    pub fn draw(&self, countdown: &str, style: &Style, caption: &str) -> io::Result<()> {
+        // the terminal could have been resized since the last tick
+        let (rows, columns) = self.term.size();
+        let frame = frame(
+            countdown,
+            style,
+            caption,
+            usize::from(columns),
+            usize::from(rows),
+        );
+        // go to the top left and clear everything before drawing
+        self.term.write_str(&format!("\x1b[H\x1b[2J{}", frame))
+    }
+}
+
+impl Drop for Screen<'_> {
+    fn drop(&mut self) {
+        // there's nowhere left to report a problem to while leaving
+        let _ = self.term.write_str("\x1b[?1049l");
+        let _ = self.term.show_cursor();
+    }
+}
+
+#[test]
+fn big_digits() {
+    let lines = big_text("1:0").unwrap();
+    assert_eq!(lines.len(), GLYPH_HEIGHT);
+    assert_eq!(lines[0], "  ██        ██████");
+    assert_eq!(lines[1], "████    ██  ██  ██");
+    assert!(lines
+        .iter()
+        .all(|line| measure_text_width(line) == measure_text_width(&lines[0])));
+    assert_eq!(big_text("9:00am"), None);
+}
+
+#[test]
+fn frame_centering() {
+    let plain = Style::new();
+    let full = frame("00:00:01", &plain, "until 9:00pm today", 80, 24);
+    let lines: Vec<_> = full.split("\r\n").collect();
+    // the digits and a blank line and the caption, in the middle of 24 rows
+    assert_eq!(lines.len(), 8 + GLYPH_HEIGHT + 2);
+    assert!(lines[..8].iter().all(|line| line.is_empty()));
+    assert_eq!(
+        lines.last().unwrap(),
+        &format!("{}until 9:00pm today", " ".repeat(31))
+    );
+
+    // too narrow for the big digits
+    let narrow = frame("00:00:01", &plain, "until 9:00pm today", 20, 24);
+    assert!(narrow.contains("      00:00:01\r\n\r\n"));
+}
@@ -0,0 +1,26 @@
+#![deny(missing_docs)]
+//! Opinionated parsing of times of day and durations, as used by the `alrm` countdown timer.
+//!
+//! There's also `countdown`, which gives the time left until a target, for driving a display.
+//!
+//! Example
+//! ```
+//! use alrm::opinionated_time_parsing;
+//! use chrono::NaiveTime;
+//!
+//! assert_eq!(
+//!     opinionated_time_parsing("9:30pm").unwrap(),
+//!     NaiveTime::from_hms(21, 30, 0)
+//! );
+//! ```
+
+mod countdown;
+mod parse;
+
+pub use crate::countdown::{countdown, Countdown};
+pub use crate::parse::{
+    opinionated_time_parsing, opinionated_time_parsing_with, parse_relative_duration, parse_target,
+    parse_target_with, parse_with_spans, parse_zoned_target, parse_zoned_target_with,
+    parse_zoned_targets, parse_zoned_targets_with, Field, FieldSpans, ParseOptions, ParsedTime,
+    StringSection, TimeParseError, Zone, FORMATS,
+};
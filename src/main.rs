@@ -8,87 +8,2935 @@
 //! alrm 9       # prints the time until 9:00 am
 //! alrm 9:30pm  # prints the time until 9:30 pm
 //! alrm 9:00 -u # counts down to 9:00 am and then exits
+//! alrm 1h30m   # prints the time until an hour and a half from now
+//! alrm 2024-12-25 9am # prints the time until 9:00 am on December 25th, 2024
+//! alrm 14:00 UTC # prints the time until 2:00 pm in UTC
+//! alrm -c      # counts up from zero until interrupted
 //! ```
 
-mod parse;
-
-use crate::parse::opinionated_time_parsing;
-use chrono::Local;
-use clap::Parser;
-use console::{Style, Term};
+use alrm::{
+    countdown, opinionated_time_parsing_with, parse_relative_duration, parse_zoned_target_with,
+    parse_zoned_targets_with, Countdown, ParseOptions, ParsedTime, TimeParseError, Zone, FORMATS,
+};
+use chrono::{
+    Date, DateTime, Datelike, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Timelike, Utc, Weekday,
+};
+use clap::{ArgEnum, Parser, Subcommand};
+use console::{measure_text_width, Color, Key, Style, Term};
+use directories::ProjectDirs;
 use hhmmss::Hhmmss;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, Receiver};
 use std::thread;
 
+mod fullscreen;
+
 /// A quick countdown timer
 #[derive(Parser, Debug)]
-#[clap(version)]
+// `alrm -30` is a (bad) time, not a flag
+#[clap(version, allow_negative_numbers = true)]
 struct Cli {
     /// update console countdown
     #[clap(
         long,
         short,
+        overrides_with = "no-update",
         long_help = "Update the countdown until once the time has passed and then exit"
     )]
     update: bool,
 
+    /// don't update the countdown, even if the config file says to
+    #[clap(long, overrides_with = "update")]
+    no_update: bool,
+
+    /// start the countdown over the next day once it finishes
+    #[clap(
+        long,
+        short,
+        long_help = "Once the time has passed, count down to the same time tomorrow, and so on until interrupted with Ctrl-C. Implies --update"
+    )]
+    repeat: bool,
+
+    /// start the countdown over every DURATION
+    #[clap(
+        long,
+        value_name = "DURATION",
+        parse(try_from_str = parse_period),
+        conflicts_with = "repeat",
+        long_help = "Once the time has passed, count down to DURATION after it, like 45m, and so on until interrupted with Ctrl-C. If TIME isn't given, the first countdown is DURATION from now. Implies --update"
+    )]
+    repeat_every: Option<Duration>,
+
+    /// stop after the countdown has finished N times
+    #[clap(
+        long,
+        value_name = "N",
+        default_value_t = 0,
+        long_help = "Stop after the countdown has finished N times with --repeat, --repeat-every, or --pomodoro, alerting each time including the last. 0 means to keep going until interrupted"
+    )]
+    repeat_count: u32,
+
+    /// stop repeating once it's past TIME
+    #[clap(
+        long,
+        value_name = "TIME",
+        long_help = "Stop once the next countdown with --repeat, --repeat-every, --pomodoro, or --chime would finish after TIME, like 5pm, instead of starting it. TIME is the next time the clock reads it, as of when alrm starts. Along with --repeat-count, whichever comes first is the last"
+    )]
+    repeat_until: Option<String>,
+
+    /// offer to snooze for DURATION once the countdown finishes
+    #[clap(
+        long,
+        value_name = "DURATION",
+        parse(try_from_str = parse_period),
+        conflicts_with_all = &["json", "silent", "all"],
+        long_help = "Once the countdown finishes, wait for a key to be pressed. Pressing s counts down DURATION more, like 5m, and any other key carries on as usual. Only works in a terminal"
+    )]
+    snooze: Option<Duration>,
+
+    /// alternate between work and break countdowns
+    #[clap(
+        long,
+        conflicts_with_all = &["time", "at", "repeat", "repeat-every"],
+        long_help = "Count down a work session, then a break, then work again, and so on until interrupted with Ctrl-C, alerting at the end of each one. The sessions are 25 minutes of work and 5 minutes of break by default. Implies --update"
+    )]
+    pomodoro: bool,
+
+    /// how long each work session is with --pomodoro
+    #[clap(
+        long,
+        value_name = "DURATION",
+        default_value = "25m",
+        parse(try_from_str = parse_period),
+        long_help = "Make each work session with --pomodoro last DURATION, like 50m"
+    )]
+    pomodoro_work: Duration,
+
+    /// how long each break is with --pomodoro
+    #[clap(
+        long,
+        value_name = "DURATION",
+        default_value = "5m",
+        parse(try_from_str = parse_period),
+        long_help = "Make each break with --pomodoro last DURATION, like 10m"
+    )]
+    pomodoro_break: Duration,
+
+    /// ring the bell on the hour, or every half or quarter hour
+    #[clap(
+        long,
+        arg_enum,
+        value_name = "GRANULARITY",
+        conflicts_with_all = &["time", "at", "repeat", "repeat-every", "pomodoro", "schedule", "schedule-json", "all", "wait", "from", "snooze"],
+        long_help = "Count down to the next quarter hour, half hour, or hour on the clock, ring the bell, and then count down to the one after that, like a clock chiming, until interrupted with Ctrl-C. Implies --update and --align"
+    )]
+    chime: Option<Chime>,
+
+    /// how often to update the countdown, in milliseconds
+    #[clap(
+        long,
+        value_name = "MILLIS",
+        parse(try_from_str = parse_interval),
+        long_help = "Wait MILLIS milliseconds between updates with --update or --count-up. Must be at least 50, and defaults to 1000"
+    )]
+    interval: Option<u64>,
+
+    /// change the seconds in step with the system clock
+    #[clap(
+        long,
+        long_help = "Wait only until the next whole second on the system clock before the first update with --update, and then every --interval after that, so the seconds shown change at the same moment as the clock's"
+    )]
+    align: bool,
+
+    /// don't flash a banner when the countdown finishes
+    #[clap(
+        long,
+        short,
+        long_help = "Don't flash a \"Time's up!\" banner when the countdown finishes with --update"
+    )]
+    quiet: bool,
+
+    /// don't show the countdown at all, just wait and then alert
+    #[clap(
+        long,
+        conflicts_with = "json",
+        long_help = "Don't print anything while counting down, and just wait until the time has passed to --beep or --notify. Implies --update"
+    )]
+    silent: bool,
+
+    /// just wait until the time, like sleep, without showing or alerting anything
+    #[clap(
+        long,
+        conflicts_with_all = &["update", "silent", "json", "all", "fullscreen", "repeat", "repeat-every", "pomodoro", "schedule", "schedule-json"],
+        long_help = "Wait until the time has passed without printing anything, and then exit, like a sleep that understands times of day. The clock is checked every minute, so this still finishes on time if the clock changes or the computer sleeps in the meantime"
+    )]
+    wait: bool,
+
+    /// take over the whole terminal with big digits
+    #[clap(
+        long,
+        conflicts_with_all = &["json", "silent", "bar"],
+        long_help = "Clear the terminal and show the countdown in big digits in the middle of it, for a countdown on a screen of its own. Whatever was on the terminal comes back once alrm exits. Implies --update"
+    )]
+    fullscreen: bool,
+
+    /// count down to every time at once
+    #[clap(
+        long,
+        conflicts_with_all = &["json", "silent", "bar", "fullscreen", "repeat", "repeat-every", "pomodoro"],
+        long_help = "When several times are given, count down to all of them at once, one line each, instead of one after another. Each line is marked done once its time has passed, and alrm exits once they all have. Implies --update"
+    )]
+    all: bool,
+
+    /// turn red when there's less than this much time left
+    #[clap(
+        long,
+        value_name = "DURATION",
+        parse(try_from_str = parse_relative_duration),
+        long_help = "Show the countdown in red once there is less than DURATION left, like 5m or 1h30m. Only has an effect with --update"
+    )]
+    warn_at: Option<Duration>,
+
+    /// show a progress bar
+    #[clap(
+        long,
+        long_help = "Show a progress bar filling up from when alrm was started until the time has passed. Only has an effect with --update"
+    )]
+    bar: bool,
+
+    /// ring the terminal bell when the countdown finishes
+    #[clap(
+        long,
+        short,
+        overrides_with = "no-beep",
+        long_help = "Ring the terminal bell once the time has passed. Only has an effect with --update"
+    )]
+    beep: bool,
+
+    /// don't ring the bell, even if the config file says to
+    #[clap(long, overrides_with = "beep")]
+    no_beep: bool,
+
+    /// how many times to ring the bell
+    #[clap(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        long_help = "Ring the terminal bell N times when --beep is given"
+    )]
+    beep_count: u32,
+
+    /// send a desktop notification when the countdown finishes
+    #[cfg(feature = "notify")]
+    #[clap(
+        long,
+        long_help = "Send a desktop notification once the time has passed. Only has an effect with --update"
+    )]
+    notify: bool,
+
+    /// what to do when the countdown finishes: bell, notify, or exec:COMMAND
+    #[clap(
+        long,
+        value_name = "ACTION",
+        multiple_occurrences = true,
+        parse(try_from_str = parse_on_complete),
+        long_help = "Do ACTION once the time has passed, where ACTION is bell to ring the terminal bell like --beep, notify to send a desktop notification like --notify, or exec:COMMAND to run COMMAND in the shell, like exec:\"mpv ding.mp3\". The command is given the time counted down to as ALRM_TARGET, and any --message as ALRM_MESSAGE. Can be given more than once. Only has an effect with --update"
+    )]
+    on_complete: Vec<OnComplete>,
+
+    /// count up from zero like a stopwatch
+    #[clap(
+        long,
+        short,
+        conflicts_with_all = &["time", "at"],
+        long_help = "Count up the time elapsed since starting, like a stopwatch, until interrupted with Ctrl-C"
+    )]
+    count_up: bool,
+
+    /// show how long it's been since a time
+    #[clap(
+        long,
+        value_name = "TIME",
+        conflicts_with_all = &["time", "at", "count-up", "json"],
+        long_help = "Print how long it has been since TIME, like 9am, instead of counting down to it. If TIME hasn't happened yet today, it's the one yesterday. With --update, keeps counting up until interrupted with Ctrl-C"
+    )]
+    elapsed_since: Option<String>,
+
+    /// pretend it's TIME today, to see how long it would be from then
+    #[clap(
+        long,
+        value_name = "TIME",
+        conflicts_with_all = &["update", "repeat", "repeat-every", "pomodoro", "fullscreen", "schedule", "schedule-json", "silent", "all", "wait", "count-up", "elapsed-since"],
+        long_help = "Work out the countdown as if it were TIME today, like 8am, instead of right now. `alrm 9am --from 8am` is 1 hour, and `alrm 7am --from 8am` is tomorrow. Only for a countdown that's printed once, since an updating one has to follow the real time"
+    )]
+    from: Option<String>,
+
+    /// log each finished countdown to a file
+    #[clap(
+        long,
+        value_name = "PATH",
+        parse(from_os_str),
+        long_help = "Add a line to the end of PATH every time a countdown finishes, with when it finished, the time it was counting down to, and any --name and --message. Each line is a JSON object. If PATH can't be written to, alrm warns and keeps going"
+    )]
+    output_file: Option<PathBuf>,
+
+    /// show the time left in the terminal's title
+    #[clap(
+        long,
+        conflicts_with = "json",
+        long_help = "Show the time left in the title of the terminal window or tab, so it can be seen while the terminal is hidden. The title is put back once alrm exits, where the terminal allows it. Only has an effect with --update, when printing to a terminal"
+    )]
+    title: bool,
+
+    /// show only the time left, without what it's until
+    #[clap(
+        long,
+        conflicts_with_all = &["all", "fullscreen"],
+        long_help = "Show just the time left, like 00:25:00, leaving off what it's counting down to, like \"until 9:00pm today\". --name and --message are still shown"
+    )]
+    bare: bool,
+
+    /// show the time left with units, like 3m 20s
+    #[clap(
+        long,
+        conflicts_with = "fullscreen",
+        long_help = "Show the time left with the units labeled, like 2d 0h 0m 0s or 3m 20s, instead of like 48:00:00 or 00:03:20. Units bigger than the time left are left off"
+    )]
+    units: bool,
+
+    /// label to show with the countdown
+    #[clap(
+        long,
+        short,
+        value_name = "LABEL",
+        long_help = "Show LABEL at the start of every line, to tell several timers apart"
+    )]
+    name: Option<String>,
+
+    /// print the date and time being counted down to
+    #[clap(
+        long,
+        long_help = "Print the full date and time that TIME was understood as, like 2024-06-01 21:00:00 -07:00, and then exit. Ignores --update"
+    )]
+    show_target: bool,
+
+    /// print how the time was understood and exit
+    #[clap(
+        long,
+        long_help = "Print how TIME was understood, like \"9:00am tomorrow, 14h 3m from now\", and then exit without counting down. With several times, each one is printed on its own line"
+    )]
+    dry_run: bool,
+
+    /// show the target time in 24-hour format
+    #[clap(
+        long = "format-24",
+        conflicts_with = "format-12",
+        long_help = "Show the time being counted down to in 24-hour format, like 21:00. By default, the format follows your locale"
+    )]
+    format_24: bool,
+
+    /// show the target time in 12-hour format
+    #[clap(
+        long = "format-12",
+        long_help = "Show the time being counted down to in 12-hour format, like 9:00pm. This is the default if your locale's format can't be told"
+    )]
+    format_12: bool,
+
+    /// print only the number of seconds left
+    #[clap(
+        long,
+        long_help = "Print only the number of seconds until the time, for use in scripts like `sleep $(alrm 9pm --until-seconds)`, and then exit. Ignores --update"
+    )]
+    until_seconds: bool,
+
+    /// the color to show the countdown in
+    #[clap(
+        long,
+        arg_enum,
+        value_name = "NAME",
+        long_help = "Show the time left in NAME instead of yellow"
+    )]
+    color: Option<CountdownColor>,
+
+    /// how to round the time left to whole seconds
+    #[clap(
+        long,
+        arg_enum,
+        value_name = "MODE",
+        default_value = "ceil",
+        long_help = "Round the time left to whole seconds with MODE before showing it. ceil rounds up, so the countdown reaches 00:00:00 right at the time, floor rounds down, and nearest rounds to whichever is closest"
+    )]
+    round: Rounding,
+
+    /// don't use colors
+    #[clap(
+        long,
+        long_help = "Print without colors. Colors are also turned off when the NO_COLOR environment variable is set, or when printing somewhere other than a terminal"
+    )]
+    no_color: bool,
+
+    /// what the countdown is for
+    #[clap(
+        long,
+        short,
+        value_name = "TEXT",
+        long_help = "Show TEXT alongside the countdown, and again once the time has passed. With --notify, TEXT is used for the notification"
+    )]
+    message: Option<String>,
+
+    /// print machine-readable JSON
+    #[clap(
+        long,
+        global = true,
+        long_help = "Print the countdown as a JSON object instead of colored text. With --update, one JSON object is printed per line each tick"
+    )]
+    json: bool,
+
+    /// allow an am/pm that agrees with a 24-hour time
+    #[clap(
+        long,
+        global = true,
+        long_help = "Accept an am/pm after a 24-hour time as long as they agree, so 18:30 pm is 6:30 pm. One that disagrees, like 18:30 am, is still an error"
+    )]
+    lenient: bool,
+
+    /// read numbers too big to be an hour as minutes
+    #[clap(
+        long,
+        long_help = "Read a number on its own that's too big to be an hour, like 90, as that many minutes from now. Up to 23 is still an hour, 24 is midnight at the end of the day, and three or four digits, like 0900, are still military time"
+    )]
+    minutes_if_big: bool,
+
+    /// don't warn when a time has just passed and is counted down to tomorrow
+    #[clap(
+        long,
+        long_help = "Don't print a note when TIME has already passed today and is more than 23 hours away tomorrow. The note is never printed with --json, --silent, or --wait"
+    )]
+    no_rollover_warning: bool,
+
+    /// list the ways a time can be written
+    #[clap(
+        long,
+        long_help = "Print the patterns that TIME can be written in, with an example of each, and then exit"
+    )]
+    list_formats: bool,
+
+    /// read the times to count down to from a file
+    #[clap(
+        long,
+        value_name = "PATH",
+        parse(from_os_str),
+        conflicts_with_all = &["time", "at", "pomodoro"],
+        long_help = "Count down to each time in PATH in order, alerting as each one passes. Each line of PATH is a time, optionally followed by `|` and a message to show with it, like `9:30am | stand-up`. Blank lines and lines starting with `#` are skipped. Implies --update"
+    )]
+    schedule: Option<PathBuf>,
+
+    /// read the times to count down to from JSON
+    #[clap(
+        long,
+        value_name = "PATH",
+        parse(from_os_str),
+        conflicts_with_all = &["time", "at", "pomodoro", "schedule"],
+        long_help = "Like --schedule, but PATH is JSON, or - to read it from stdin. It can be an array of entries, or one entry per line, where each entry looks like {\"time\": \"9:30am\", \"message\": \"stand-up\"}. The message can be left out. Implies --update"
+    )]
+    schedule_json: Option<PathBuf>,
+
+    /// time to count down to, instead of giving it as TIME
+    #[clap(
+        long,
+        value_name = "TIME",
+        conflicts_with = "time",
+        long_help = "Count down to TIME, the same as giving TIME on its own but easier to read in scripts. Only one of --at or TIME can be given"
+    )]
+    at: Option<String>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// time to count down to
     #[clap(
-        long_help = "Count down to TIME. If TIME has already passed today, then count down the TIME tomorrow.",
+        long_help = "Count down to TIME. If TIME has already passed today, then count down the TIME tomorrow. TIME can also be a duration like 30m or 1h30m, which counts down from now. Several times can be given separated by spaces or commas, which counts down to whichever comes first, and then the next with --update. If TIME isn't given, it is read from stdin when stdin isn't a terminal. A TIME starting with a -, other than a plain number like -30, has to come after --, like `alrm -- -30m`, so it isn't taken for a flag.",
         use_value_delimiter = false,
         multiple_values = true
     )]
     time: Vec<String>,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Cli::parse();
-    let term = Term::stdout();
+/// The colors the countdown can be shown in with `--color`
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum CountdownColor {
+    Red,
+    Green,
+    Blue,
+    Cyan,
+    Magenta,
+    Yellow,
+    White,
+}
 
-    let time_str = args.time.join(" ");
-    let time = match opinionated_time_parsing(&time_str) {
-        Ok(time) => time,
-        Err(err) => {
-            eprint!("{}", err);
-            std::process::exit(1);
+impl From<CountdownColor> for Color {
+    fn from(color: CountdownColor) -> Self {
+        match color {
+            CountdownColor::Red => Color::Red,
+            CountdownColor::Green => Color::Green,
+            CountdownColor::Blue => Color::Blue,
+            CountdownColor::Cyan => Color::Cyan,
+            CountdownColor::Magenta => Color::Magenta,
+            CountdownColor::Yellow => Color::Yellow,
+            CountdownColor::White => Color::White,
         }
-    };
+    }
+}
 
-    let mut date = Local::today();
+/// The ways the time left can be rounded to whole seconds with `--round`
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Rounding {
+    Floor,
+    Ceil,
+    Nearest,
+}
 
-    if time < Local::now().time() {
-        date = date.succ();
+impl Rounding {
+    /// Round `duration` to a whole number of seconds
+    fn apply(self, duration: Duration) -> Duration {
+        let millis = duration.num_milliseconds();
+        let offset = match self {
+            Rounding::Floor => 0,
+            Rounding::Ceil => 999,
+            Rounding::Nearest => 500,
+        };
+        // rounding towards negative infinity, even for times that have already passed
+        Duration::seconds((millis + offset).div_euclid(1000))
     }
+}
 
-    let date = date.and_time(time).unwrap();
+/// Something to do when a countdown finishes, for `--on-complete`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OnComplete {
+    /// Ring the terminal bell, like `--beep`
+    Bell,
+    /// Send a desktop notification, like `--notify`
+    #[cfg(feature = "notify")]
+    Notify,
+    /// Run a command in the shell
+    Exec(String),
+}
 
-    let yellow = Style::new().bright().yellow();
-    loop {
-        let time_left = date - Local::now();
+/// Parse an `--on-complete` action, like `bell` or `exec:mpv ding.mp3`
+fn parse_on_complete(s: &str) -> Result<OnComplete, String> {
+    if let Some(command) = s.strip_prefix("exec:") {
+        if command.trim().is_empty() {
+            return Err("exec: needs a command to run after it".to_string());
+        }
+        return Ok(OnComplete::Exec(command.to_string()));
+    }
+    match s {
+        "bell" => Ok(OnComplete::Bell),
+        #[cfg(feature = "notify")]
+        "notify" => Ok(OnComplete::Notify),
+        #[cfg(not(feature = "notify"))]
+        "notify" => Err("alrm was built without desktop notifications".to_string()),
+        _ => Err("expected bell, notify, or exec:COMMAND".to_string()),
+    }
+}
+
+/// How often to ring the bell with `--chime`
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Chime {
+    Quarter,
+    Half,
+    Hour,
+}
+
+impl Chime {
+    /// How many minutes apart the chimes are
+    fn minutes(self) -> u32 {
+        match self {
+            Chime::Quarter => 15,
+            Chime::Half => 30,
+            Chime::Hour => 60,
+        }
+    }
+}
+
+/// Things to do other than counting down
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// print how long it is from one time to another
+    #[clap(
+        long_about = "Print how long it is from FROM to TO, like `alrm between 9am 5pm` for 8h 0m. If TO is earlier in the day than FROM, it's the TO the next day"
+    )]
+    Between {
+        /// the time to start from
+        from: String,
+        /// the time to end at
+        to: String,
+    },
+}
+
+impl Cli {
+    /// How to parse times given as of `now`
+    fn parse_options(&self, now: NaiveTime) -> ParseOptions {
+        ParseOptions {
+            lenient: self.lenient,
+            now: Some(now),
+            minutes_if_big: self.minutes_if_big,
+        }
+    }
+
+    /// The style to show the time left in
+    fn countdown_style(&self) -> Style {
+        let color = self.color.unwrap_or(CountdownColor::Yellow);
+        Style::new().bright().fg(color.into())
+    }
 
-        let relative_day = if date.date() == Local::today() {
-            "today"
+    /// The time left as it's shown, in `--units` or as `HH:MM:SS`
+    fn format_time_left(&self, time_left: Duration) -> String {
+        if self.units {
+            format_units(time_left)
         } else {
-            "tomorrow"
-        };
-        let output = format!(
-            "{} until {} {}",
-            yellow.apply_to(time_left.hhmmss()),
-            time.format("%-I:%M%P"),
-            relative_day
-        );
-        term.write_line(&output)?;
+            format_duration(time_left)
+        }
+    }
 
-        if !args.update {
-            break;
+    /// Milliseconds to wait between updates
+    fn interval(&self) -> u64 {
+        self.interval.unwrap_or(DEFAULT_INTERVAL)
+    }
+}
+
+/// Update once a second, since that's as precise as the display is
+const DEFAULT_INTERVAL: u64 = 1000;
+
+/// Updating faster than this doesn't make much sense for a display in seconds
+const MIN_INTERVAL: u64 = 50;
+
+/// The longest `--wait` sleeps before checking the clock again
+const MAX_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
+
+fn parse_interval(s: &str) -> Result<u64, String> {
+    let interval: u64 = s.parse().map_err(|err| format!("{}", err))?;
+    if interval < MIN_INTERVAL {
+        return Err(format!("interval must be at least {}ms", MIN_INTERVAL));
+    }
+    Ok(interval)
+}
+
+/// Defaults for some of the flags, read from `config.toml` in the user's config directory
+/// (e.g. `~/.config/alrm/config.toml`). Flags given on the command line take precedence
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    update: bool,
+    beep: bool,
+    interval: Option<u64>,
+    color: Option<bool>,
+}
+
+impl Config {
+    /// Read the config file, if there is one. A config file that can't be understood is
+    /// warned about and then ignored, rather than stopping the countdown
+    fn load() -> Self {
+        let path = match ProjectDirs::from("", "", "alrm") {
+            Some(dirs) => dirs.config_dir().join("config.toml"),
+            None => return Self::default(),
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                eprintln!("warning: could not read {}: {}", path.display(), err);
+                return Self::default();
+            }
+        };
+        match Self::parse(&contents) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("warning: ignoring {}: {}", path.display(), err);
+                Self::default()
+            }
         }
+    }
 
-        thread::sleep(std::time::Duration::from_millis(1000));
+    fn parse(contents: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(contents).map_err(|err| err.to_string())?;
+        if let Some(interval) = config.interval {
+            parse_interval(&interval.to_string())?;
+        }
+        Ok(config)
+    }
 
-        if date < Local::now() {
-            break;
+    /// Fill in anything that wasn't given on the command line
+    fn apply(self, args: &mut Cli) {
+        // a countdown from a pretend time can't keep up with the real one
+        args.update |= self.update && args.from.is_none() && !args.no_update;
+        args.beep |= self.beep && !args.no_beep;
+        args.interval = args.interval.or(self.interval);
+        // asking for a countdown color asks for colors
+        if self.color == Some(false) && args.color.is_none() {
+            args.no_color = true;
         }
+    }
+}
 
-        term.clear_last_lines(1)?;
+/// Parse the duration for `--repeat-every`, `--snooze`, and the pomodoro lengths, which all
+/// have to actually move the countdown forwards
+fn parse_period(s: &str) -> Result<Duration, String> {
+    let period = parse_relative_duration(s).map_err(|err| err.to_string())?;
+    if period <= Duration::zero() {
+        return Err("DURATION must be longer than zero".to_string());
     }
-    Ok(())
+    Ok(period)
+}
+
+/// Countdowns longer than this are probably a typo, and are too long to be useful anyways
+const MAX_DAYS: i64 = 365;
+
+fn too_far() -> ! {
+    eprintln!(
+        "Error: that's more than {} days away, which is too far to count down to",
+        MAX_DAYS
+    );
+    std::process::exit(1);
+}
+
+/// The state of the countdown at a single tick, for `--json` output
+#[derive(Serialize, Debug)]
+struct CountdownStatus {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phase: Option<&'static str>,
+    target: String,
+    remaining_seconds: i64,
+    relative_day: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+/// A countdown that finished, as logged to `--output-file`
+#[derive(Serialize, Debug)]
+struct Completion<'a> {
+    finished: String,
+    target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+}
+
+/// Add `completion` to the end of the log at `path` as a line of JSON
+fn log_completion(path: &Path, completion: &Completion) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(completion)?)
+}
+
+/// Something to count down to, with the zone it's in and the message to show with it
+type Target = (ParsedTime, Option<Zone>, Option<String>);
+
+/// Parse the lines of a `--schedule` file into targets, each with the message after its `|` if
+/// there is one. An error comes with the line number it's on, counting from 1
+fn parse_schedule(
+    contents: &str,
+    options: ParseOptions,
+) -> Result<Vec<Target>, (usize, TimeParseError)> {
+    let mut targets = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let (time, message) = match line.split_once('|') {
+            Some((time, message)) => (time, Some(message.trim())),
+            None => (line, None),
+        };
+        let (target, zone) =
+            parse_zoned_target_with(time.trim(), options).map_err(|err| (number + 1, err))?;
+        let message = message.filter(|message| !message.is_empty());
+        targets.push((target, zone, message.map(str::to_string)));
+    }
+    Ok(targets)
+}
+
+/// One time to count down to in `--schedule-json`
+#[derive(Deserialize, Debug)]
+struct ScheduleEntry {
+    time: String,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// Why `--schedule-json` couldn't be read
+#[derive(Debug)]
+enum ScheduleJsonError {
+    /// It isn't JSON at all
+    Json(serde_json::Error),
+    /// The entry at this index isn't shaped like a `ScheduleEntry`
+    Entry(usize, serde_json::Error),
+    /// The time in the entry at this index couldn't be parsed
+    Time(usize, TimeParseError),
+}
+
+/// Parse `--schedule-json` into targets, from either a JSON array of entries or one entry after
+/// another. Entries are counted from 0 in the order they come in, across the whole input
+fn parse_schedule_json(
+    contents: &str,
+    options: ParseOptions,
+) -> Result<Vec<Target>, ScheduleJsonError> {
+    let mut entries = Vec::new();
+    for value in serde_json::Deserializer::from_str(contents).into_iter() {
+        match value.map_err(ScheduleJsonError::Json)? {
+            serde_json::Value::Array(values) => entries.extend(values),
+            value => entries.push(value),
+        }
+    }
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let entry: ScheduleEntry = serde_json::from_value(value)
+                .map_err(|err| ScheduleJsonError::Entry(index, err))?;
+            let (target, zone) = parse_zoned_target_with(entry.time.trim(), options)
+                .map_err(|err| ScheduleJsonError::Time(index, err))?;
+            let message = entry.message.filter(|message| !message.trim().is_empty());
+            Ok((target, zone, message))
+        })
+        .collect()
+}
+
+/// The message to show with the countdown to `date`, from a schedule or else `--message`
+fn message_for<'a>(
+    messages: &'a HashMap<DateTime<Local>, String>,
+    args: &'a Cli,
+    date: DateTime<Local>,
+) -> Option<&'a str> {
+    messages
+        .get(&date)
+        .map(String::as_str)
+        .or(args.message.as_deref())
+}
+
+/// The part of a `--pomodoro` cycle being counted down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Work,
+    Break,
+}
+
+impl Phase {
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Work => "work",
+            Phase::Break => "break",
+        }
+    }
+
+    /// The phase after this one, and how long it lasts
+    fn next(self, args: &Cli) -> (Phase, Duration) {
+        match self {
+            Phase::Work => (Phase::Break, args.pomodoro_break),
+            Phase::Break => (Phase::Work, args.pomodoro_work),
+        }
+    }
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut args = Cli::parse();
+    Config::load().apply(&mut args);
+    let term = Term::stdout();
+    let mut start = Local::now();
+
+    // console already turns colors off when stdout or stderr isn't a terminal
+    if args.no_color || env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+
+    if args.list_formats {
+        list_formats();
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if args.count_up {
+        return count_up(&term, &args);
+    }
+
+    if let Some(Command::Between { from, to }) = &args.command {
+        let options = args.parse_options(Local::now().time());
+        let (from, to) = match (
+            opinionated_time_parsing_with(from, options),
+            opinionated_time_parsing_with(to, options),
+        ) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(err), _) | (_, Err(err)) => {
+                eprint!("{}", err);
+                std::process::exit(1);
+            }
+        };
+        let between = time_between(from, to);
+        if args.json {
+            term.write_line(&serde_json::json!({ "seconds": between.num_seconds() }).to_string())?;
+        } else {
+            term.write_line(&describe_duration(between))?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(since) = &args.elapsed_since {
+        let now = Local::now();
+        let options = args.parse_options(now.time());
+        let time = match opinionated_time_parsing_with(since, options) {
+            Ok(time) => time,
+            Err(err) => {
+                eprint!("{}", err);
+                std::process::exit(1);
+            }
+        };
+        return elapsed_since(&term, &args, resolve_past(time, now));
+    }
+
+    let from = args.from.as_ref().map(|from| {
+        match opinionated_time_parsing_with(from, args.parse_options(Local::now().time())) {
+            Ok(time) => pretend_now(time, Local::today()),
+            Err(err) => {
+                eprint!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    });
+    // what time it is, or what time it's pretending to be with --from
+    let clock = || from.unwrap_or_else(Local::now);
+    let now = clock();
+    let until = args.repeat_until.as_ref().map(|until| {
+        match opinionated_time_parsing_with(until, args.parse_options(now.time())) {
+            Ok(time) => resolve_target(time, now),
+            Err(err) => {
+                eprint!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    });
+    let mut time_str = match &args.at {
+        Some(at) => at.clone(),
+        None => args.time.join(" "),
+    };
+    let targets = match args.repeat_every {
+        _ if args.pomodoro => vec![(ParsedTime::Relative(args.pomodoro_work), None, None)],
+        _ if args.chime.is_some() => {
+            let first = next_chime(args.chime.unwrap(), now);
+            vec![(ParsedTime::DateTime(first.naive_local()), None, None)]
+        }
+        _ if args.schedule.is_some() => {
+            let path = args.schedule.as_ref().unwrap();
+            let contents = match fs::read_to_string(path) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    eprintln!("Error: could not read {}: {}", path.display(), err);
+                    std::process::exit(1);
+                }
+            };
+            match parse_schedule(&contents, args.parse_options(now.time())) {
+                Ok(targets) if !targets.is_empty() => targets,
+                Ok(_) => {
+                    eprintln!("Error: there are no times in {}", path.display());
+                    std::process::exit(1);
+                }
+                Err((line, err)) => {
+                    eprintln!("In {}, on line {}:", path.display(), line);
+                    eprint!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ if args.schedule_json.is_some() => {
+            let path = args.schedule_json.as_ref().unwrap();
+            let (contents, name) = if path.as_os_str() == "-" {
+                (io::read_to_string(io::stdin()), "stdin".to_string())
+            } else {
+                (fs::read_to_string(path), path.display().to_string())
+            };
+            let contents = match contents {
+                Ok(contents) => contents,
+                Err(err) => {
+                    eprintln!("Error: could not read {}: {}", name, err);
+                    std::process::exit(1);
+                }
+            };
+            match parse_schedule_json(&contents, args.parse_options(now.time())) {
+                Ok(targets) if !targets.is_empty() => targets,
+                Ok(_) => {
+                    eprintln!("Error: there are no times in {}", name);
+                    std::process::exit(1);
+                }
+                Err(ScheduleJsonError::Json(err)) => {
+                    eprintln!("Error: {} is not valid JSON: {}", name, err);
+                    std::process::exit(1);
+                }
+                Err(ScheduleJsonError::Entry(index, err)) => {
+                    eprintln!(
+                        "Error: entry {} in {} is not a schedule entry: {}",
+                        index, name, err
+                    );
+                    std::process::exit(1);
+                }
+                Err(ScheduleJsonError::Time(index, err)) => {
+                    eprintln!("In {}, in entry {}:", name, index);
+                    eprint!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        // `--repeat-every 45m` on its own starts the first countdown right away
+        Some(period) if time_str.is_empty() => {
+            vec![(ParsedTime::Relative(period), None, None)]
+        }
+        _ => {
+            // let times be piped in, like `echo 9:30pm | alrm`
+            if time_str.is_empty() && !io::stdin().is_terminal() {
+                io::stdin().lock().read_line(&mut time_str)?;
+                time_str = time_str.trim().to_string();
+            }
+            let options = args.parse_options(now.time());
+            match parse_zoned_targets_with(&time_str, options) {
+                Ok(targets) => targets
+                    .into_iter()
+                    .map(|(target, zone)| (target, zone, None))
+                    .collect(),
+                Err(err) => {
+                    eprint!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+    };
+
+    // times of day that have already passed today, and so were moved to tomorrow
+    let mut rolled_over = Vec::new();
+    // messages from --schedule or --schedule-json, by the date they go with
+    let mut messages = HashMap::new();
+    // snoozed countdowns, by the time they were scheduled for before snoozing
+    let mut snoozed_from = HashMap::new();
+    let mut dates: Vec<_> = targets
+        .into_iter()
+        .map(|(target, zone, message)| {
+            let date = match zone {
+                None => resolve(target, &Local, now),
+                Some(Zone::Fixed(offset)) => resolve(target, &offset, now),
+                #[cfg(feature = "tz")]
+                Some(Zone::Named(zone)) => resolve(target, &zone, now),
+            };
+            if matches!(target, ParsedTime::Absolute(_)) && date.date() != now.date() {
+                rolled_over.push(date);
+            }
+            if let Some(message) = message {
+                messages.entry(date).or_insert(message);
+            }
+            date
+        })
+        .collect();
+    if dates
+        .iter()
+        .any(|date| *date - clock() > Duration::days(MAX_DAYS))
+    {
+        too_far();
+    }
+    dates.sort();
+    // with several targets, skip ahead to the first one that hasn't passed yet
+    while dates.len() > 1 && dates[0] < clock() {
+        dates.remove(0);
+    }
+    let mut date = dates.remove(0);
+
+    if warns_of_rollover(&args, rolled_over.contains(&date), date - now) {
+        eprintln!(
+            "note: {} already passed today; counting down to tomorrow",
+            date.format(time_format(&args))
+        );
+    }
+
+    if args.show_target {
+        let target = date.format("%Y-%m-%d %H:%M:%S %:z").to_string();
+        if args.json {
+            term.write_line(&serde_json::json!({ "target": target }).to_string())?;
+        } else {
+            term.write_line(&target)?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if args.dry_run {
+        let now = clock();
+        for date in std::iter::once(date).chain(dates) {
+            let relative_day = relative_day(date.date(), now.date());
+            let output = if args.json {
+                serde_json::to_string(&CountdownStatus {
+                    name: args.name.clone(),
+                    phase: args.pomodoro.then(|| Phase::Work.name()),
+                    target: date.time().format("%H:%M:%S").to_string(),
+                    remaining_seconds: (date - now).num_seconds(),
+                    relative_day,
+                    message: message_for(&messages, &args, date).map(str::to_string),
+                })?
+            } else {
+                format!(
+                    "{} {}, {}",
+                    date.format(time_format(&args)),
+                    relative_day,
+                    describe_offset(date - now)
+                )
+            };
+            term.write_line(&output)?;
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if args.until_seconds {
+        term.write_line(&(date - clock()).num_seconds().to_string())?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if args.wait {
+        wait_until(countdown(date), thread::sleep);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if args.all {
+        let dates = std::iter::once(date).chain(dates).collect();
+        return count_down_all(&term, &args, dates, &messages, &handle_interrupts()?);
+    }
+
+    let update = args.update
+        || args.repeat
+        || args.repeat_every.is_some()
+        || args.chime.is_some()
+        || args.pomodoro
+        || args.fullscreen
+        || args.schedule.is_some()
+        || args.schedule_json.is_some()
+        || args.silent;
+    let interrupts = if update {
+        Some(handle_interrupts()?)
+    } else {
+        None
+    };
+
+    let color = args.countdown_style();
+    let red = Style::new().bright().red().bold();
+    let mut finished = 0;
+    let mut phase = Phase::Work;
+    // a terminal is needed to take over, otherwise it's just a normal countdown
+    let screen = if args.fullscreen && term.is_term() {
+        Some(fullscreen::Screen::enter(&term)?)
+    } else {
+        None
+    };
+    // what to leave on the terminal once the full screen countdown is put away
+    let mut leave_behind = None;
+    let title = if args.title && update && term.is_term() {
+        Some(Title::take(&term)?)
+    } else {
+        None
+    };
+    // the time left as of the last tick, and when that was on a clock that can't be changed
+    let mut last_tick: Option<(Duration, std::time::Instant)> = None;
+    // how many rows the countdown took up when it was last drawn, since it can wrap
+    let mut drawn = 0;
+    // the widest the time left has been in this countdown, to keep the line from shifting
+    let mut time_width = 0;
+    // the chimes are on whole seconds, so the countdown to them should be too
+    let mut align = args.align || args.chime.is_some();
+    loop {
+        let time = date.time();
+        let now = clock();
+        let message = message_for(&messages, &args, date);
+        // once the time has passed, an updating countdown ends at zero rather than however late
+        // it is, but a one-off still shows how long ago a time in the past was
+        let done = update && date <= now;
+        let time_left = if done { Duration::zero() } else { date - now };
+        let style = match args.warn_at {
+            Some(warn_at) if update && time_left < warn_at => &red,
+            _ => &color,
+        };
+
+        if let Some((previous, then)) = last_tick {
+            let elapsed = Duration::from_std(then.elapsed()).unwrap_or_else(|_| Duration::zero());
+            let tolerance = Duration::milliseconds(args.interval() as i64);
+            let jump = clock_jump(previous, time_left, elapsed, tolerance);
+            // the full screen countdown is redrawn from scratch anyway
+            let shown = !done && !args.json && !args.silent && screen.is_none();
+            if let Some(jump) = jump.filter(|_| shown) {
+                // the countdown goes on from the new time, but say why it suddenly changed
+                rewrite_line(&term, drawn, "")?;
+                drawn = 0;
+                eprintln!(
+                    "note: the clock jumped {} {}",
+                    if jump > Duration::zero() {
+                        "forward"
+                    } else {
+                        "back"
+                    },
+                    describe_duration(jump)
+                );
+            }
+        }
+        last_tick = Some((time_left, std::time::Instant::now()));
+        // only what's shown is rounded, so that clock jumps are still measured exactly
+        let time_left = args.round.apply(time_left);
+        let shown_left = args.format_time_left(time_left);
+        time_width = time_width.max(shown_left.len());
+
+        let relative_day = relative_day(date.date(), now.date());
+        let output = if args.json {
+            serde_json::to_string(&CountdownStatus {
+                name: args.name.clone(),
+                phase: args.pomodoro.then(|| phase.name()),
+                target: time.format("%H:%M:%S").to_string(),
+                remaining_seconds: time_left.num_seconds(),
+                relative_day,
+                message: message.map(str::to_string),
+            })?
+        } else {
+            // the full screen countdown shows the time left on its own, in big digits
+            let mut output = match screen {
+                Some(_) => until_target(time, &relative_day, time_format(&args)),
+                None if args.bare => style
+                    .apply_to(format!("{:>1$}", shown_left, time_width))
+                    .to_string(),
+                None => render_line(
+                    &shown_left,
+                    time,
+                    &relative_day,
+                    style,
+                    time_format(&args),
+                    time_width,
+                ),
+            };
+            if args.pomodoro {
+                output = format!("{} {}", label(phase.name()), output);
+            }
+            if let Some(name) = &args.name {
+                output = format!("{} {}", label(name), output);
+            }
+            if done && !args.bare {
+                output = format!("{} (now)", output);
+            }
+            if let Some(message) = message {
+                output = format!("{} - {}", output, message);
+            }
+            if args.bar && update {
+                // the terminal could have been resized since the last tick
+                let (_, columns) = term.size();
+                let width = usize::from(columns).saturating_sub(measure_text_width(&output) + 3);
+                let elapsed = (Local::now() - start).num_milliseconds() as f64;
+                let total = (date - start).num_milliseconds() as f64;
+                output = format!("{} [{}]", output, progress_bar(elapsed / total, width));
+            }
+            output
+        };
+        if args.silent {
+            // nothing to show
+        } else if args.json || !update {
+            term.write_line(&output)?;
+        } else if let Some(screen) = &screen {
+            screen.draw(&format_duration(time_left), style, &output)?;
+        } else {
+            drawn = rewrite_line(&term, drawn, &output)?;
+        }
+        if let Some(title) = &title {
+            let left = if done {
+                "Time's up!".to_string()
+            } else {
+                shown_left
+            };
+            title.set(&format!(
+                "{} {}",
+                args.name.as_deref().unwrap_or("alrm"),
+                left
+            ))?;
+        }
+
+        // only updating countdowns listen for Ctrl-C, everything else is done after one line
+        let interrupts = match &interrupts {
+            Some(interrupts) => interrupts,
+            None => break,
+        };
+
+        if done {
+            // leave the finished countdown on screen
+            if !args.json && !args.silent && screen.is_none() {
+                term.write_line("")?;
+            }
+            drawn = 0;
+            let banner = format!("Time's up! It's {}", time.format(time_format(&args)));
+            if let Some(screen) = &screen {
+                if !args.quiet {
+                    let caption = Style::new().bold().reverse().apply_to(&banner);
+                    screen.draw(&format_duration(time_left), style, &caption.to_string())?;
+                }
+                leave_behind = Some(banner);
+            } else if !args.quiet
+                && !args.silent
+                && !args.json
+                && flash(&term, interrupts, &banner)?
+            {
+                return Ok(interrupted(&term)?);
+            }
+            alert(&term, &args, time, message)?;
+            // there has to be someone there to press a key
+            let snooze = args
+                .snooze
+                .filter(|_| term.is_term() && io::stdin().is_terminal());
+            if let Some(snooze) = snooze {
+                let prompt = format!(
+                    "Press s to snooze for {}, or any other key to dismiss",
+                    describe_duration(snooze)
+                );
+                match &screen {
+                    Some(screen) => screen.draw(&format_duration(time_left), style, &prompt)?,
+                    None => term.write_str(&prompt)?,
+                }
+                let key = match term.read_key() {
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                        return Ok(interrupted(&term)?)
+                    }
+                    key => key?,
+                };
+                if screen.is_none() {
+                    term.clear_line()?;
+                }
+                if let Key::Char('s' | 'S') = key {
+                    let snoozed = Local::now() + snooze;
+                    if let Some(message) = messages.get(&date).cloned() {
+                        messages.insert(snoozed, message);
+                    }
+                    let scheduled = snoozed_from.remove(&date).unwrap_or(date);
+                    snoozed_from.insert(snoozed, scheduled);
+                    dates.push(snoozed);
+                    dates.sort();
+                    start = Local::now();
+                    date = dates.remove(0);
+                    last_tick = None;
+                    time_width = 0;
+                    continue;
+                }
+            }
+            finished += 1;
+            // repeats follow the schedule, not however long this one was snoozed for
+            let scheduled = snoozed_from.remove(&date).unwrap_or(date);
+            match after_finishing(&args, scheduled, finished, &mut phase, until) {
+                Repeat::Finished => break,
+                Repeat::Next(next) => {
+                    if let Some(message) = messages.get(&date).cloned() {
+                        messages.insert(next, message);
+                    }
+                    dates.push(next);
+                    dates.sort();
+                }
+                Repeat::Stopped => {}
+            }
+            if dates.is_empty() {
+                break;
+            }
+            start = date;
+            date = dates.remove(0);
+            // a new countdown isn't a jump from the old one
+            last_tick = None;
+            time_width = 0;
+            continue;
+        }
+
+        let millis = if std::mem::take(&mut align) {
+            until_next_second(Local::now())
+        } else {
+            args.interval()
+        };
+        if wait(interrupts, millis) {
+            return Ok(interrupted(&term)?);
+        }
+    }
+    drop(screen);
+    if let Some(banner) = leave_behind {
+        term.write_line(&banner)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Whether to point out that a time has already passed today, for a countdown that's `left` away
+/// and was `rolled_over` to tomorrow. `alrm 9am` at 9:01am is nearly a day away, which probably
+/// isn't what was meant, but there's nowhere to say so with --json, --wait, or --silent
+fn warns_of_rollover(args: &Cli, rolled_over: bool, left: Duration) -> bool {
+    !args.json
+        && !args.wait
+        && !args.silent
+        && !args.no_rollover_warning
+        && rolled_over
+        && left > Duration::hours(23)
+}
+
+/// Work out when `target` is as of `now`, reading any time of day off of a clock in `zone`
+fn resolve<Z: TimeZone>(target: ParsedTime, zone: &Z, now: DateTime<Local>) -> DateTime<Local> {
+    match target {
+        ParsedTime::Absolute(time) => {
+            resolve_target(time, now.with_timezone(zone)).with_timezone(&Local)
+        }
+        ParsedTime::Relative(duration) => now
+            .checked_add_signed(duration)
+            .unwrap_or_else(|| too_far()),
+        ParsedTime::DateTime(datetime) => local_datetime(zone, datetime).with_timezone(&Local),
+        // unlike a plain time of day, this is on that day even if it has already passed
+        ParsedTime::OnDay(days, time) => {
+            let date = now.with_timezone(zone).date() + Duration::days(i64::from(days));
+            local_datetime(zone, date.naive_local().and_time(time)).with_timezone(&Local)
+        }
+        // already an exact moment, so there's no clock to read it off of
+        ParsedTime::Instant(datetime) => Utc.from_utc_datetime(&datetime).with_timezone(&Local),
+        ParsedTime::OnDate(month, day, time) => {
+            resolve_date(month, day, time, now.with_timezone(zone)).with_timezone(&Local)
+        }
+        ParsedTime::OnWeekday(weekday, time) => {
+            resolve_weekday(weekday, time, now.with_timezone(zone)).with_timezone(&Local)
+        }
+    }
+}
+
+/// The next time it's `time` on a `weekday`, as of `now`. That's today if it's that day and the
+/// time hasn't passed yet, and a week from today if it has
+fn resolve_weekday<Z: TimeZone>(
+    weekday: Weekday,
+    time: NaiveTime,
+    now: DateTime<Z>,
+) -> DateTime<Z> {
+    let zone = now.timezone();
+    let now = now.with_nanosecond(0).unwrap();
+    let days = (weekday.num_days_from_monday() + 7 - now.weekday().num_days_from_monday()) % 7;
+    let date = now.date().naive_local() + Duration::days(i64::from(days));
+    let next = local_datetime(&zone, date.and_time(time));
+    if next >= now {
+        next
+    } else {
+        local_datetime(&zone, (date + Duration::weeks(1)).and_time(time))
+    }
+}
+
+/// The next time it's `time` on the `day` of `month`, as of `now`, which is next year once it's
+/// passed this year. Feb 29 waits for the next leap year
+fn resolve_date<Z: TimeZone>(
+    month: u32,
+    day: u32,
+    time: NaiveTime,
+    now: DateTime<Z>,
+) -> DateTime<Z> {
+    let zone = now.timezone();
+    let now = now.with_nanosecond(0).unwrap();
+    // there's a leap year at least every 8 years
+    (now.year()..now.year() + 9)
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+        .map(|date| local_datetime(&zone, date.and_time(time)))
+        .find(|date| *date >= now)
+        .unwrap_or_else(|| too_far())
+}
+
+/// What comes after a countdown finishes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Repeat {
+    /// It's finished as many times as `--repeat-count` asked for, so everything is done
+    Finished,
+    /// It repeats, next finishing at this time
+    Next(DateTime<Local>),
+    /// It doesn't repeat, or the next one would finish after `--repeat-until`, so it's on to any
+    /// other times there are
+    Stopped,
+}
+
+/// What comes after the countdown to `date` has finished for the `finished`th time, moving the
+/// `--pomodoro` `phase` along. `until` is when `--repeat-until` stops it
+fn after_finishing(
+    args: &Cli,
+    date: DateTime<Local>,
+    finished: u32,
+    phase: &mut Phase,
+    until: Option<DateTime<Local>>,
+) -> Repeat {
+    if finished == args.repeat_count {
+        return Repeat::Finished;
+    }
+    // go from the old target rather than now, so the countdowns don't drift later and later
+    let next = if args.repeat {
+        // the same time on the clock tomorrow, which isn't 24 hours away if the clocks
+        // change overnight
+        local_datetime(&Local, date.naive_local() + Duration::days(1))
+    } else if let Some(period) = args.repeat_every {
+        date + period
+    } else if let Some(chime) = args.chime {
+        next_chime(chime, date)
+    } else if args.pomodoro {
+        let (next, length) = phase.next(args);
+        *phase = next;
+        date + length
+    } else {
+        return Repeat::Stopped;
+    };
+    match until {
+        Some(until) if next > until => Repeat::Stopped,
+        _ => Repeat::Next(next),
+    }
+}
+
+/// The first time after `after` that a clock in its time zone is on a multiple of the `chime`'s
+/// minutes past midnight. This only depends on the clock, so counting from one chime to the next
+/// never drifts. A chime in the hour that's repeated when the clocks go back only rings once
+fn next_chime<Z: TimeZone>(chime: Chime, after: DateTime<Z>) -> DateTime<Z> {
+    let zone = after.timezone();
+    let minutes = chime.minutes();
+    let since_midnight = after.time().num_seconds_from_midnight() / 60;
+    let mut next = after.date().naive_local().and_hms(0, 0, 0)
+        + Duration::minutes(i64::from((since_midnight / minutes + 1) * minutes));
+    loop {
+        let chime = local_datetime(&zone, next);
+        if chime > after {
+            return chime;
+        }
+        next += Duration::minutes(i64::from(minutes));
+    }
+}
+
+/// The moment it's `time` on `today`, for `--from`
+fn pretend_now(time: NaiveTime, today: Date<Local>) -> DateTime<Local> {
+    local_datetime(&Local, today.naive_local().and_time(time))
+}
+
+/// The next time the clock will read `time`, as of `now`
+///
+/// A time of day that has already passed is tomorrow, but one in the current second is still
+/// today, with nothing left to count down
+fn resolve_target<Z: TimeZone>(time: NaiveTime, now: DateTime<Z>) -> DateTime<Z> {
+    let mut date = now.date();
+
+    if time < now.time().with_nanosecond(0).unwrap() {
+        date = date.succ();
+    }
+
+    local_datetime(&now.timezone(), date.naive_local().and_time(time))
+}
+
+/// Work out when `time` last was as of `now`, which is yesterday if it hasn't happened yet today
+fn resolve_past<Z: TimeZone>(time: NaiveTime, now: DateTime<Z>) -> DateTime<Z> {
+    let mut date = now.date();
+
+    if time > now.time() {
+        date = date.pred();
+    }
+
+    local_datetime(&now.timezone(), date.naive_local().and_time(time))
+}
+
+/// The moment a clock in `zone` reads `datetime`
+///
+/// When the clocks go back, a time that happens twice is the first one. When they go forward,
+/// a time that's skipped over, like 2:30am when 2am becomes 3am, is the moment they change
+fn local_datetime<Z: TimeZone>(zone: &Z, datetime: NaiveDateTime) -> DateTime<Z> {
+    let mut datetime = datetime;
+    // no time zone skips more than a day, so this is only a backstop
+    for _ in 0..24 * 60 {
+        match zone.from_local_datetime(&datetime) {
+            LocalResult::Single(moment) | LocalResult::Ambiguous(moment, _) => return moment,
+            LocalResult::None => {
+                datetime = datetime
+                    .checked_add_signed(Duration::minutes(1))
+                    .unwrap_or_else(|| too_far())
+            }
+        }
+    }
+    zone.from_utc_datetime(&datetime)
+}
+
+/// How far the clock jumped between two ticks of a countdown, if it jumped further than
+/// `tolerance`. `elapsed` is how long really went by, which `previous - time_left` should match.
+/// The jump is positive when the clock went forward and negative when it went back
+fn clock_jump(
+    previous: Duration,
+    time_left: Duration,
+    elapsed: Duration,
+    tolerance: Duration,
+) -> Option<Duration> {
+    let jump = previous - time_left - elapsed;
+    if jump > tolerance || -jump > tolerance {
+        Some(jump)
+    } else {
+        None
+    }
+}
+
+/// How long it is from `from` to `to`, going on into the next day if `to` is earlier in the day
+fn time_between(from: NaiveTime, to: NaiveTime) -> Duration {
+    let between = to - from;
+    if between < Duration::zero() {
+        between + Duration::days(1)
+    } else {
+        between
+    }
+}
+
+/// Countries that usually write the time in 12-hour format, by the territory code used in locales
+const TWELVE_HOUR_TERRITORIES: [&str; 10] =
+    ["US", "CA", "AU", "NZ", "PH", "IN", "PK", "BD", "EG", "SA"];
+
+/// The `strftime` format to show the target time in
+fn time_format(args: &Cli) -> &'static str {
+    let twelve_hour = if args.format_12 || args.format_24 {
+        args.format_12
+    } else {
+        locale_prefers_12_hour().unwrap_or(true)
+    };
+    if twelve_hour {
+        "%-I:%M%P"
+    } else {
+        "%H:%M"
+    }
+}
+
+/// Whether the user's locale writes times in 12-hour format, if it can be told
+fn locale_prefers_12_hour() -> Option<bool> {
+    // the same order of precedence that libc uses
+    let locale = ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .find_map(|var| env::var(var).ok().filter(|value| !value.is_empty()))
+        // macOS and Windows don't usually set these, so ask the system instead
+        .or_else(sys_locale::get_locale)?;
+    prefers_12_hour(&locale)
+}
+
+/// Whether `locale` writes times in 12-hour format, if it has a territory to tell by
+fn prefers_12_hour(locale: &str) -> Option<bool> {
+    // locales look like `en_US.UTF-8` or `en-US`, but `C` and `POSIX` don't have a territory
+    let territory = locale.split(['.', '@']).next()?.split(['_', '-']).nth(1)?;
+    Some(TWELVE_HOUR_TERRITORIES.contains(&territory))
+}
+
+/// Describe `date` in relation to `today`, e.g. "tomorrow" or "in 3 days"
+fn relative_day(date: Date<Local>, today: Date<Local>) -> String {
+    match (date - today).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "yesterday".to_string(),
+        days if days > 1 => format!("in {} days", days),
+        days => format!("{} days ago", -days),
+    }
+}
+
+/// Describe how far away something is in words, like `14h 3m from now` or `5m 10s ago`,
+/// leaving off the smaller units once they stop mattering
+fn describe_offset(offset: Duration) -> String {
+    let amount = describe_duration(offset);
+    // rounded the same way as the amount, so a moment ago isn't "0s ago"
+    if offset <= Duration::milliseconds(-500) {
+        format!("{} ago", amount)
+    } else {
+        format!("{} from now", amount)
+    }
+}
+
+/// Describe a length of time in words, like `14h 3m`, leaving off the smaller units once they
+/// stop mattering. The sign is left off too
+fn describe_duration(duration: Duration) -> String {
+    // a moment has already passed since the target was worked out, so `1h30m` would come out
+    // as 1h 29m if this rounded down
+    let seconds = (duration.num_milliseconds() as f64 / 1000.0).round() as i64;
+    let total = seconds.abs();
+    let (days, hours, minutes, seconds_left) = (
+        total / 86400,
+        total / 3600 % 24,
+        total / 60 % 60,
+        total % 60,
+    );
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds_left)
+    } else {
+        format!("{}s", seconds_left)
+    }
+}
+
+/// The countdown as shown to the user, like `00:25:00 until 9:00pm today`. The time left is
+/// padded on the left to at least `width` columns, so that the rest of the line stays put when
+/// it gets shorter, like going from `1d 00:00:00` to `23:59:59`
+fn render_line(
+    time_left: &str,
+    target: NaiveTime,
+    relative_day: &str,
+    style: &Style,
+    time_format: &str,
+    width: usize,
+) -> String {
+    format!(
+        "{} {}",
+        style.apply_to(format!("{:>1$}", time_left, width)),
+        until_target(target, relative_day, time_format)
+    )
+}
+
+/// What the countdown is counting down to, like `until 9:00pm today`
+fn until_target(target: NaiveTime, relative_day: &str, time_format: &str) -> String {
+    format!("until {} {}", target.format(time_format), relative_day)
+}
+
+/// Format a duration as `HH:MM:SS`, with the number of days in front if it's a day or longer, like `2d 03:15:00`
+fn format_duration(duration: Duration) -> String {
+    let days = duration.num_days();
+    if days == 0 {
+        return duration.hhmmss();
+    }
+    let rest = duration - Duration::days(days);
+    // the sign is already on the days
+    let rest = if rest < Duration::zero() { -rest } else { rest };
+    format!("{}d {}", days, rest.hhmmss())
+}
+
+/// Format a duration with its units labeled for `--units`, like `2d 0h 0m 0s` or `3m 20s`. The
+/// units bigger than the duration are left off, but not the ones after them
+fn format_units(duration: Duration) -> String {
+    let total = duration.num_seconds().abs();
+    let units = [
+        (total / 86400, "d"),
+        (total / 3600 % 24, "h"),
+        (total / 60 % 60, "m"),
+        (total % 60, "s"),
+    ];
+    // there's always the seconds, even when there's no time left at all
+    let first = units[..3]
+        .iter()
+        .position(|(amount, _)| *amount > 0)
+        .unwrap_or(3);
+    let labeled: Vec<_> = units[first..]
+        .iter()
+        .map(|(amount, unit)| format!("{}{}", amount, unit))
+        .collect();
+    let sign = if duration.num_seconds() < 0 { "-" } else { "" };
+    format!("{}{}", sign, labeled.join(" "))
+}
+
+/// The `--name` of a timer, styled to stand apart from the countdown
+fn label(name: &str) -> String {
+    let cyan = Style::new().cyan();
+    format!("{}", cyan.apply_to(format!("[{}]", name)))
+}
+
+/// Print every pattern from `FORMATS` next to its example, lined up in two columns
+fn list_formats() {
+    let width = FORMATS
+        .iter()
+        .map(|(pattern, _)| pattern.len())
+        .max()
+        .unwrap_or(0);
+    let yellow = Style::new().bright().yellow();
+    println!("TIME can be written as");
+    for (pattern, example) in FORMATS {
+        println!(
+            "  {:width$}  e.g. {}",
+            pattern,
+            yellow.apply_to(example),
+            width = width
+        );
+    }
+    println!("TIME can also be a duration like 1h30m, come after a date like 2024-12-25, or be followed by a time zone like UTC");
+}
+
+/// A bar `width` characters wide that is `fraction` of the way full
+fn progress_bar(fraction: f64, width: usize) -> String {
+    let filled = ((fraction.clamp(0.0, 1.0) * width as f64) as usize).min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// Leave the terminal tidy when a countdown that's updating is interrupted with Ctrl-C
+fn handle_interrupts() -> Result<Receiver<()>, ctrlc::Error> {
+    let (sender, receiver) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        // nobody is listening anymore if we're already on our way out
+        let _ = sender.send(());
+    })?;
+    Ok(receiver)
+}
+
+/// Block until `countdown` is over, calling `sleep` for as long as possible at a time, up to
+/// `MAX_WAIT`. Going back to the clock between sleeps keeps this on time through clock changes,
+/// and through the computer being suspended, which any one sleep might not count
+fn wait_until<C: FnMut() -> DateTime<Local>>(
+    countdown: Countdown<C>,
+    mut sleep: impl FnMut(std::time::Duration),
+) {
+    for remaining in countdown {
+        // the countdown never goes below zero, so this always fits
+        let remaining = remaining.to_std().unwrap_or_default();
+        if remaining.is_zero() {
+            break;
+        }
+        sleep(remaining.min(MAX_WAIT));
+    }
+}
+
+/// How many milliseconds it is from `now` until the next whole second, rounded up so that
+/// waking up after that long is never still in the second before
+fn until_next_second<Z: TimeZone>(now: DateTime<Z>) -> u64 {
+    // a leap second shows up as more than a billion nanoseconds
+    let nanos = 1_000_000_000 - u64::from(now.nanosecond() % 1_000_000_000);
+    nanos.div_ceil(1_000_000)
+}
+
+/// Sleep for `millis`, waking up early and returning `true` if Ctrl-C is pressed in the meantime
+fn wait(interrupts: &Receiver<()>, millis: u64) -> bool {
+    interrupts
+        .recv_timeout(std::time::Duration::from_millis(millis))
+        .is_ok()
+}
+
+/// Leave the terminal tidy after Ctrl-C, and exit the way a program killed by it would
+fn interrupted(term: &Term) -> io::Result<ExitCode> {
+    term.show_cursor()?;
+    term.write_line("")?;
+    Ok(ExitCode::from(130))
+}
+
+/// Replace the last `previous` rows, ending with the one the cursor is on, with `lines` in a
+/// single write, so there's never a moment where they're blank. The cursor is left at the end
+/// of the last line. Gives the number of rows `lines` take up once they wrap, to pass as
+/// `previous` next time
+fn rewrite_lines(term: &Term, previous: usize, lines: &[String]) -> io::Result<usize> {
+    let mut output = String::new();
+    if previous > 1 {
+        // back up to the first row
+        output.push_str(&format!("\x1b[{}A", previous - 1));
+    }
+    // `\r` goes back to the start of the row, and `\x1b[J` clears it and every row after it, so
+    // nothing is left over from longer lines
+    output.push_str("\r\x1b[J");
+    output.push_str(&lines.join("\n"));
+    term.write_str(&output)?;
+    // the terminal could have been resized since the last tick
+    let (_, columns) = term.size();
+    Ok(physical_rows(lines, usize::from(columns)))
+}
+
+/// How many rows of a terminal `columns` wide `lines` take up altogether, once each one wraps
+fn physical_rows(lines: &[String], columns: usize) -> usize {
+    lines
+        .iter()
+        .map(|line| physical_lines(measure_text_width(line), columns))
+        .sum()
+}
+
+/// Put `left` at the start of a line `width` columns wide and `right` at the end of it, with at
+/// least one space in between if they don't fit
+fn align_line(left: &str, right: &str, width: usize) -> String {
+    let padding = width
+        .saturating_sub(measure_text_width(left) + measure_text_width(right))
+        .max(1);
+    format!("{}{}{}", left, " ".repeat(padding), right)
+}
+
+/// Replace the line the cursor is on, which took up `previous` rows once it wrapped, with `line`,
+/// like `rewrite_lines`. The cursor is left at the end of the line rather than on a new one
+fn rewrite_line(term: &Term, previous: usize, line: &str) -> io::Result<usize> {
+    rewrite_lines(term, previous, &[line.to_string()])
+}
+
+/// How many rows of a terminal `columns` wide a line that's `width` columns wide takes up, once
+/// it wraps onto the next row every time it reaches the edge. Even an empty line takes up one
+fn physical_lines(width: usize, columns: usize) -> usize {
+    if columns == 0 {
+        return 1;
+    }
+    width.max(1).div_ceil(columns)
+}
+
+/// The terminal's title, taken over to show the countdown until this is dropped
+struct Title<'a> {
+    term: &'a Term,
+}
+
+impl<'a> Title<'a> {
+    /// Save the title `term` has now, for terminals that can put it back later
+    fn take(term: &'a Term) -> io::Result<Self> {
+        term.write_str("\x1b[22;0t")?;
+        Ok(Title { term })
+    }
+
+    fn set(&self, title: &str) -> io::Result<()> {
+        self.term.write_str(&format!("\x1b]0;{}\x07", title))
+    }
+}
+
+impl Drop for Title<'_> {
+    fn drop(&mut self) {
+        // terminals that can't put the old title back at least aren't left with a stale countdown
+        let _ = self.set("");
+        let _ = self.term.write_str("\x1b[23;0t");
+    }
+}
+
+/// Flash a banner a few times so the end of the countdown is hard to miss, and then leave it on
+/// screen. Returns `true` if Ctrl-C was pressed in the meantime
+fn flash(term: &Term, interrupts: &Receiver<()>, banner: &str) -> io::Result<bool> {
+    let banner = Style::new().bold().apply_to(banner);
+    // there's no point flashing something that isn't being looked at, like a file
+    if term.is_term() {
+        let mut drawn = 0;
+        for _ in 0..3 {
+            drawn = rewrite_line(term, drawn, &banner.clone().reverse().to_string())?;
+            if wait(interrupts, 200) {
+                return Ok(true);
+            }
+            drawn = rewrite_line(term, drawn, &banner.to_string())?;
+            if wait(interrupts, 200) {
+                return Ok(true);
+            }
+        }
+        rewrite_line(term, drawn, "")?;
+    }
+    term.write_line(&banner.to_string())?;
+    Ok(false)
+}
+
+/// Let the user know that the countdown to `time` has finished, however they asked to be told
+#[cfg_attr(not(feature = "notify"), allow(unused_variables))]
+fn alert(
+    term: &Term,
+    args: &Cli,
+    time: NaiveTime,
+    message: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(message) = message {
+        // with --all, the message is already on every line
+        if !args.json && !args.silent && !args.all {
+            term.write_line(message)?;
+        }
+    }
+    if let Some(path) = &args.output_file {
+        let completion = Completion {
+            finished: Local::now().format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+            target: time.format("%H:%M:%S").to_string(),
+            name: args.name.as_deref(),
+            message,
+        };
+        // losing the log isn't worth stopping a countdown over
+        if let Err(err) = log_completion(path, &completion) {
+            eprintln!("warning: could not write to {}: {}", path.display(), err);
+        }
+    }
+    if args.beep || args.chime.is_some() {
+        beep(term, args.beep_count)?;
+    }
+    #[cfg(feature = "notify")]
+    if args.notify {
+        match message {
+            Some(message) => notify(message),
+            None => notify(&format!("It's {}", time.format(time_format(args)))),
+        }
+    }
+    for action in &args.on_complete {
+        match action {
+            OnComplete::Bell => beep(term, args.beep_count)?,
+            #[cfg(feature = "notify")]
+            OnComplete::Notify => match message {
+                Some(message) => notify(message),
+                None => notify(&format!("It's {}", time.format(time_format(args)))),
+            },
+            OnComplete::Exec(command) => run(command, time, message),
+        }
+    }
+    Ok(())
+}
+
+/// Start `command` in the shell for `--on-complete exec:`, telling it about the countdown that
+/// just finished through the environment. The countdown carries on without waiting for it
+fn run(command: &str, time: NaiveTime, message: Option<&str>) {
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell
+        .arg(command)
+        .env("ALRM_TARGET", time.format("%H:%M:%S").to_string());
+    if let Some(message) = message {
+        shell.env("ALRM_MESSAGE", message);
+    }
+    match shell.spawn() {
+        // reap it once it's done, so that it doesn't linger while alrm keeps going
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(err) => eprintln!("warning: could not run {}: {}", command, err),
+    }
+}
+
+fn beep(term: &Term, count: u32) -> Result<(), Box<dyn std::error::Error>> {
+    for i in 0..count {
+        if i > 0 {
+            thread::sleep(std::time::Duration::from_millis(300));
+        }
+        ring(term)?;
+    }
+    Ok(())
+}
+
+/// Make a single beep. Not every Windows terminal rings the bell for `\x07`, so ask Windows
+/// to play its default sound instead
+#[cfg(all(windows, feature = "sound"))]
+fn ring(_term: &Term) -> io::Result<()> {
+    use winapi::um::winuser::{MessageBeep, MB_OK};
+    // this only fails if there's no sound to play, and there's nothing to be done about that
+    unsafe {
+        MessageBeep(MB_OK);
+    }
+    Ok(())
+}
+
+/// Make a single beep by ringing the terminal's bell
+#[cfg(not(all(windows, feature = "sound")))]
+fn ring(term: &Term) -> io::Result<()> {
+    term.write_str("\x07")
+}
+
+#[cfg(feature = "notify")]
+fn notify(body: &str) {
+    if let Err(err) = notify_rust::Notification::new()
+        .summary("alrm")
+        .body(body)
+        .show()
+    {
+        eprintln!("warning: could not send notification: {}", err);
+    }
+}
+
+/// Count down to every one of `dates` at once for `--all`, one line each, until they've all
+/// passed. Each one alerts as it finishes
+fn count_down_all(
+    term: &Term,
+    args: &Cli,
+    dates: Vec<DateTime<Local>>,
+    messages: &HashMap<DateTime<Local>, String>,
+    interrupts: &Receiver<()>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let color = args.countdown_style();
+    let red = Style::new().bright().red().bold();
+    let green = Style::new().green().bold();
+    let mut finished = vec![false; dates.len()];
+    let mut drawn = 0;
+    let mut align = args.align;
+    loop {
+        let now = Local::now();
+        // the terminal could have been resized since the last tick
+        let (_, columns) = term.size();
+        let mut just_finished = Vec::new();
+        let lines: Vec<_> = dates
+            .iter()
+            .zip(&mut finished)
+            .map(|(date, finished)| {
+                let time = date.time();
+                let mut left = until_target(
+                    time,
+                    &relative_day(date.date(), now.date()),
+                    time_format(args),
+                );
+                if let Some(name) = &args.name {
+                    left = format!("{} {}", label(name), left);
+                }
+                let message = message_for(messages, args, *date);
+                if let Some(message) = message {
+                    left = format!("{} - {}", left, message);
+                }
+                if *date <= now && !*finished {
+                    *finished = true;
+                    just_finished.push((time, message));
+                }
+                let right = if *finished {
+                    green.apply_to("done".to_string())
+                } else {
+                    let time_left = args.round.apply(*date - now);
+                    let style = match args.warn_at {
+                        Some(warn_at) if time_left < warn_at => &red,
+                        _ => &color,
+                    };
+                    style.apply_to(args.format_time_left(time_left))
+                };
+                align_line(&left, &right.to_string(), usize::from(columns))
+            })
+            .collect();
+        drawn = rewrite_lines(term, drawn, &lines)?;
+
+        for (time, message) in just_finished {
+            alert(term, args, time, message)?;
+        }
+        if finished.iter().all(|finished| *finished) {
+            term.write_line("")?;
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        let millis = if std::mem::take(&mut align) {
+            until_next_second(Local::now())
+        } else {
+            args.interval()
+        };
+        if wait(interrupts, millis) {
+            return Ok(interrupted(term)?);
+        }
+    }
+}
+
+/// Show how long it's been since `since` for `--elapsed-since`, once or, with `--update`, until
+/// interrupted
+fn elapsed_since(
+    term: &Term,
+    args: &Cli,
+    since: DateTime<Local>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let interrupts = if args.update {
+        Some(handle_interrupts()?)
+    } else {
+        None
+    };
+
+    let color = args.countdown_style();
+    let mut drawn = 0;
+    loop {
+        let now = Local::now();
+        let mut output = format!(
+            "{} since {} {}",
+            color.apply_to(format_duration(now - since)),
+            since.format(time_format(args)),
+            relative_day(since.date(), now.date())
+        );
+        if let Some(name) = &args.name {
+            output = format!("{} {}", label(name), output);
+        }
+
+        let interrupts = match &interrupts {
+            Some(interrupts) => interrupts,
+            None => {
+                term.write_line(&output)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+        };
+        drawn = rewrite_line(term, drawn, &output)?;
+
+        if wait(interrupts, args.interval()) {
+            return Ok(interrupted(term)?);
+        }
+    }
+}
+
+fn count_up(term: &Term, args: &Cli) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let interrupts = handle_interrupts()?;
+    let start = Local::now();
+
+    let color = args.countdown_style();
+    let mut drawn = 0;
+    loop {
+        let elapsed = Local::now() - start;
+        let mut output = format!("elapsed {}", color.apply_to(elapsed.hhmmss()));
+        if let Some(name) = &args.name {
+            output = format!("{} {}", label(name), output);
+        }
+        drawn = rewrite_line(term, drawn, &output)?;
+
+        if wait(&interrupts, args.interval()) {
+            return Ok(interrupted(term)?);
+        }
+    }
+}
+
+#[test]
+fn config_parsing() {
+    assert_eq!(Config::parse("").unwrap(), Config::default());
+    assert_eq!(
+        Config::parse("update = true\nbeep = true\ninterval = 500\ncolor = false").unwrap(),
+        Config {
+            update: true,
+            beep: true,
+            interval: Some(500),
+            color: Some(false),
+        }
+    );
+    assert!(Config::parse("update = maybe").is_err());
+    assert!(Config::parse("updaet = true").is_err());
+    assert!(Config::parse("interval = 10").is_err());
+}
+
+#[test]
+fn config_overrides() {
+    let config = || Config::parse("update = true\nbeep = true\ncolor = false").unwrap();
+    let mut args = Cli::parse_from(["alrm", "9am"]);
+    config().apply(&mut args);
+    assert!(args.update && args.beep && args.no_color);
+    let mut args = Cli::parse_from(["alrm", "9am", "--no-update", "--no-beep", "--color", "red"]);
+    config().apply(&mut args);
+    assert!(!args.update && !args.beep && !args.no_color);
+    // the last of a flag and its negation wins
+    let mut args = Cli::parse_from(["alrm", "9am", "-u", "--no-update", "--no-beep", "-b"]);
+    config().apply(&mut args);
+    assert!(!args.update && args.beep);
+}
+
+#[test]
+fn duration_formatting() {
+    assert_eq!(format_duration(Duration::zero()), "00:00:00");
+    assert_eq!(format_duration(Duration::seconds(90)), "00:01:30");
+    assert_eq!(format_duration(Duration::hours(23)), "23:00:00");
+    assert_eq!(
+        format_duration(Duration::days(2) + Duration::minutes(195)),
+        "2d 03:15:00"
+    );
+    assert_eq!(format_duration(Duration::seconds(-90)), "-00:01:30");
+    assert_eq!(
+        format_duration(-(Duration::days(2) + Duration::minutes(195))),
+        "-2d 03:15:00"
+    );
+}
+
+#[test]
+fn target_resolution() {
+    let now = Local.ymd(2024, 3, 1).and_hms(14, 0, 0);
+    assert_eq!(
+        resolve_target(NaiveTime::from_hms(9, 0, 0), now),
+        Local.ymd(2024, 3, 2).and_hms(9, 0, 0)
+    );
+    assert_eq!(
+        resolve_target(NaiveTime::from_hms(21, 0, 0), now),
+        Local.ymd(2024, 3, 1).and_hms(21, 0, 0)
+    );
+    // rolling over to tomorrow works across the end of a month, too
+    assert_eq!(
+        resolve_target(
+            NaiveTime::from_hms(0, 0, 0),
+            Local.ymd(2024, 2, 29).and_hms(23, 0, 0)
+        ),
+        Local.ymd(2024, 3, 1).and_hms(0, 0, 0)
+    );
+}
+
+#[test]
+fn from_resolution() {
+    let args = Cli::parse_from(["alrm", "9am", "--from", "8am"]);
+    let from = opinionated_time_parsing_with(
+        args.from.as_deref().unwrap(),
+        args.parse_options(NaiveTime::from_hms(0, 0, 0)),
+    )
+    .unwrap();
+    let now = pretend_now(from, Local.ymd(2024, 3, 1));
+    assert_eq!(now, Local.ymd(2024, 3, 1).and_hms(8, 0, 0));
+    let nine = resolve_target(NaiveTime::from_hms(9, 0, 0), now);
+    assert_eq!(nine - now, Duration::hours(1));
+    // it would already be past 7am, so that's tomorrow
+    assert_eq!(
+        resolve_target(NaiveTime::from_hms(7, 0, 0), now),
+        Local.ymd(2024, 3, 2).and_hms(7, 0, 0)
+    );
+    assert!(Cli::try_parse_from(["alrm", "9am", "--from", "8am", "-u"]).is_err());
+}
+
+#[test]
+fn past_resolution() {
+    let now = Local.ymd(2024, 3, 1).and_hms(14, 0, 0);
+    assert_eq!(
+        resolve_past(NaiveTime::from_hms(9, 0, 0), now),
+        Local.ymd(2024, 3, 1).and_hms(9, 0, 0)
+    );
+    // it's not 9pm yet, so the last one was yesterday
+    assert_eq!(
+        resolve_past(NaiveTime::from_hms(21, 0, 0), now),
+        Local.ymd(2024, 2, 29).and_hms(21, 0, 0)
+    );
+    assert_eq!(resolve_past(now.time(), now), now);
+}
+
+#[cfg(feature = "tz")]
+#[test]
+fn clock_change_resolution() {
+    use chrono_tz::America::New_York;
+
+    // the clocks went from 2am to 3am, so 2:30am never happened
+    let now = New_York.ymd(2024, 3, 10).and_hms(1, 0, 0);
+    let skipped = resolve_target(NaiveTime::from_hms(2, 30, 0), now);
+    assert_eq!(skipped, New_York.ymd(2024, 3, 10).and_hms(3, 0, 0));
+    assert_eq!(skipped - now, Duration::hours(1));
+
+    // and then back from 2am to 1am, so 1:30am happened twice
+    let now = New_York.ymd(2024, 11, 3).and_hms(0, 0, 0);
+    let twice = resolve_target(NaiveTime::from_hms(1, 30, 0), now);
+    assert_eq!(twice - now, Duration::minutes(90));
+    assert_eq!(
+        resolve_past(NaiveTime::from_hms(1, 30, 0), now + Duration::hours(4)),
+        twice
+    );
+}
+
+#[test]
+fn clock_jumps() {
+    let second = Duration::seconds(1);
+    // a second went by and a second came off the countdown
+    assert_eq!(
+        clock_jump(Duration::seconds(60), Duration::seconds(59), second, second),
+        None
+    );
+    // the clock was set an hour ahead
+    assert_eq!(
+        clock_jump(
+            Duration::hours(2),
+            Duration::hours(1),
+            Duration::zero(),
+            second
+        ),
+        Some(Duration::hours(1))
+    );
+    assert_eq!(
+        clock_jump(Duration::minutes(5), Duration::minutes(10), second, second),
+        Some(-Duration::minutes(5) - second)
+    );
+}
+
+#[test]
+fn weekday_resolution() {
+    // a Wednesday
+    let now = Local.ymd(2024, 3, 6).and_hms(12, 0, 0);
+    let at = |weekday, hour| resolve_weekday(weekday, NaiveTime::from_hms(hour, 0, 0), now);
+    assert_eq!(at(Weekday::Fri, 9), Local.ymd(2024, 3, 8).and_hms(9, 0, 0));
+    assert_eq!(at(Weekday::Mon, 9), Local.ymd(2024, 3, 11).and_hms(9, 0, 0));
+    // later today, or already passed until next week
+    assert_eq!(
+        at(Weekday::Wed, 18),
+        Local.ymd(2024, 3, 6).and_hms(18, 0, 0)
+    );
+    assert_eq!(at(Weekday::Wed, 9), Local.ymd(2024, 3, 13).and_hms(9, 0, 0));
+    // right now is still today, like any other time
+    assert_eq!(at(Weekday::Wed, 12), now);
+
+    // read off of a pretend clock, like with --from
+    let clock = || pretend_now(NaiveTime::from_hms(23, 0, 0), Local.ymd(2024, 3, 10));
+    assert_eq!(
+        resolve(
+            ParsedTime::OnWeekday(Weekday::Mon, NaiveTime::from_hms(0, 0, 0)),
+            &Local,
+            clock()
+        ),
+        Local.ymd(2024, 3, 11).and_hms(0, 0, 0)
+    );
+}
+
+#[test]
+fn date_resolution() {
+    let now = Local.ymd(2023, 12, 25).and_hms(12, 0, 0);
+    let at = |month, day, hour| resolve_date(month, day, NaiveTime::from_hms(hour, 0, 0), now);
+    assert_eq!(at(12, 25, 18), Local.ymd(2023, 12, 25).and_hms(18, 0, 0));
+    // already passed this year
+    assert_eq!(at(12, 25, 9), Local.ymd(2024, 12, 25).and_hms(9, 0, 0));
+    assert_eq!(at(1, 1, 0), Local.ymd(2024, 1, 1).and_hms(0, 0, 0));
+    assert_eq!(at(2, 29, 0), Local.ymd(2024, 2, 29).and_hms(0, 0, 0));
+    let now = Local.ymd(2024, 3, 1).and_hms(0, 0, 0);
+    assert_eq!(
+        resolve_date(2, 29, NaiveTime::from_hms(0, 0, 0), now),
+        Local.ymd(2028, 2, 29).and_hms(0, 0, 0)
+    );
+}
+
+#[test]
+fn midnight_resolution() {
+    let midnight = NaiveTime::from_hms(0, 0, 0);
+    let now = Local.ymd(2024, 3, 1).and_hms(14, 0, 0);
+    let tomorrow = Local.ymd(2024, 3, 2).and_hms(0, 0, 0);
+    // `0` has already passed today, so it's the one tomorrow, same as `24`
+    assert_eq!(
+        resolve(ParsedTime::Absolute(midnight), &Local, now),
+        tomorrow
+    );
+    assert_eq!(
+        resolve(ParsedTime::OnDay(1, midnight), &Local, now),
+        tomorrow
+    );
+    // right at midnight, `0` is now, but `24` is still the end of the day
+    let now = Local.ymd(2024, 3, 1).and_hms(0, 0, 0);
+    assert_eq!(resolve(ParsedTime::Absolute(midnight), &Local, now), now);
+    assert_eq!(
+        resolve(ParsedTime::OnDay(1, midnight), &Local, now),
+        tomorrow
+    );
+}
+
+#[test]
+fn target_resolution_at_now() {
+    let now = Local.ymd(2024, 3, 1).and_hms_milli(9, 30, 0, 400);
+    // the target is in the current second, so it's today and already due
+    assert_eq!(
+        resolve(
+            ParsedTime::Absolute(NaiveTime::from_hms(9, 30, 0)),
+            &Local,
+            now
+        ),
+        Local.ymd(2024, 3, 1).and_hms(9, 30, 0)
+    );
+    assert_eq!(
+        resolve(
+            ParsedTime::Absolute(NaiveTime::from_hms(9, 29, 59)),
+            &Local,
+            now
+        ),
+        Local.ymd(2024, 3, 2).and_hms(9, 29, 59)
+    );
+    assert_eq!(
+        resolve(ParsedTime::Relative(Duration::zero()), &Local, now),
+        now
+    );
+}
+
+#[test]
+fn day_keyword_resolution() {
+    let now = Local.ymd(2024, 3, 1).and_hms(14, 0, 0);
+    // tomorrow even though 3pm is still to come today
+    assert_eq!(
+        resolve(
+            ParsedTime::OnDay(1, NaiveTime::from_hms(15, 0, 0)),
+            &Local,
+            now
+        ),
+        Local.ymd(2024, 3, 2).and_hms(15, 0, 0)
+    );
+    // today even though 9am has already passed, so it's counted down to as negative
+    assert_eq!(
+        resolve(
+            ParsedTime::OnDay(0, NaiveTime::from_hms(9, 0, 0)),
+            &Local,
+            now
+        ),
+        Local.ymd(2024, 3, 1).and_hms(9, 0, 0)
+    );
+}
+
+#[test]
+fn offset_description() {
+    assert_eq!(describe_offset(Duration::zero()), "0s from now");
+    assert_eq!(describe_offset(Duration::seconds(45)), "45s from now");
+    assert_eq!(describe_offset(Duration::seconds(310)), "5m 10s from now");
+    assert_eq!(
+        describe_offset(Duration::hours(14) + Duration::minutes(3)),
+        "14h 3m from now"
+    );
+    assert_eq!(
+        describe_offset(Duration::days(2) + Duration::hours(5)),
+        "2d 5h from now"
+    );
+    assert_eq!(describe_offset(Duration::minutes(-90)), "1h 30m ago");
+    assert_eq!(
+        describe_offset(Duration::minutes(90) - Duration::milliseconds(3)),
+        "1h 30m from now"
+    );
+}
+
+#[test]
+fn json_schedule_parsing() {
+    let options = ParseOptions::default();
+    let array = r#"[{"time": "7:00am", "message": "wake up"}, {"time": "8:15am UTC"}]"#;
+    let targets = parse_schedule_json(array, options).unwrap();
+    assert_eq!(targets.len(), 2);
+    assert_eq!(
+        targets[0],
+        (
+            ParsedTime::Absolute(NaiveTime::from_hms(7, 0, 0)),
+            None,
+            Some("wake up".to_string())
+        )
+    );
+    assert_eq!(
+        targets[1].1,
+        Some(Zone::Fixed(chrono::FixedOffset::east(0)))
+    );
+
+    let lines = "{\"time\": \"7am\"}\n{\"time\": \"7:30am\", \"message\": \"\"}\n";
+    let targets = parse_schedule_json(lines, options).unwrap();
+    assert_eq!(targets[1].2, None);
+
+    match parse_schedule_json(r#"[{"time": "7am"}, {"time": "7:70"}]"#, options) {
+        Err(ScheduleJsonError::Time(1, err)) => assert_eq!(err.kind(), "out_of_range"),
+        other => panic!("expected the second time to be wrong, got {:?}", other),
+    }
+    assert!(matches!(
+        parse_schedule_json(r#"[{"time": "7am"}, {"message": "no time"}]"#, options),
+        Err(ScheduleJsonError::Entry(1, _))
+    ));
+    assert!(matches!(
+        parse_schedule_json("[{", options),
+        Err(ScheduleJsonError::Json(_))
+    ));
+}
+
+#[test]
+fn waiting() {
+    use std::cell::{Cell, RefCell};
+
+    let start = Local.ymd(2024, 3, 1).and_hms(9, 0, 0);
+    let now = Cell::new(start);
+    let sleeps = RefCell::new(Vec::new());
+    let target = start + Duration::seconds(150);
+    let sleep = |duration| {
+        sleeps.borrow_mut().push(duration);
+        now.set(now.get() + Duration::from_std(duration).unwrap());
+    };
+    wait_until(Countdown::with_clock(target, || now.get()), sleep);
+    let seconds = std::time::Duration::from_secs;
+    assert_eq!(
+        *sleeps.borrow(),
+        vec![seconds(60), seconds(60), seconds(30)]
+    );
+    assert_eq!(now.get(), target);
+
+    // the computer was asleep for two minutes partway through
+    sleeps.borrow_mut().clear();
+    now.set(start);
+    let sleep = |duration| {
+        sleeps.borrow_mut().push(duration);
+        let overslept = if sleeps.borrow().len() == 1 {
+            Duration::minutes(2)
+        } else {
+            Duration::zero()
+        };
+        now.set(now.get() + Duration::from_std(duration).unwrap() + overslept);
+    };
+    wait_until(Countdown::with_clock(target, || now.get()), sleep);
+    assert_eq!(*sleeps.borrow(), vec![seconds(60)]);
+}
+
+#[test]
+fn completion_logging() {
+    let path = env::temp_dir().join(format!("alrm-completion-{}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let completion = Completion {
+        finished: "2024-03-01 09:00:01 +00:00".to_string(),
+        target: "09:00:00".to_string(),
+        name: Some("tea"),
+        message: None,
+    };
+    log_completion(&path, &completion).unwrap();
+    log_completion(&path, &completion).unwrap();
+    let line = r#"{"finished":"2024-03-01 09:00:01 +00:00","target":"09:00:00","name":"tea"}"#;
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!("{}\n{}\n", line, line)
+    );
+    fs::remove_file(&path).unwrap();
+
+    assert!(log_completion(&env::temp_dir(), &completion).is_err());
+}
+
+#[test]
+fn schedule_parsing() {
+    let schedule = "# morning\n7:00am | wake up\n\n7:30 am|\n  8:15am UTC | leave  \n";
+    let targets = parse_schedule(schedule, ParseOptions::default()).unwrap();
+    assert_eq!(
+        targets,
+        vec![
+            (
+                ParsedTime::Absolute(NaiveTime::from_hms(7, 0, 0)),
+                None,
+                Some("wake up".to_string())
+            ),
+            (
+                ParsedTime::Absolute(NaiveTime::from_hms(7, 30, 0)),
+                None,
+                None
+            ),
+            (
+                ParsedTime::Absolute(NaiveTime::from_hms(8, 15, 0)),
+                Some(Zone::Fixed(chrono::FixedOffset::east(0))),
+                Some("leave".to_string())
+            ),
+        ]
+    );
+
+    let (line, err) = parse_schedule("7am\n7:70 | oops", ParseOptions::default()).unwrap_err();
+    assert_eq!(line, 2);
+    assert_eq!(err.kind(), "out_of_range");
+}
+
+#[test]
+fn between_times() {
+    let nine = NaiveTime::from_hms(9, 0, 0);
+    let five = NaiveTime::from_hms(17, 0, 0);
+    assert_eq!(describe_duration(time_between(nine, five)), "8h 0m");
+    // 9am comes after 5pm the next morning
+    assert_eq!(time_between(five, nine), Duration::hours(16));
+    assert_eq!(time_between(nine, nine), Duration::zero());
+}
+
+#[test]
+fn locale_detection() {
+    assert_eq!(prefers_12_hour("en_US.UTF-8"), Some(true));
+    assert_eq!(prefers_12_hour("en-US"), Some(true));
+    assert_eq!(prefers_12_hour("de_DE.UTF-8"), Some(false));
+    assert_eq!(prefers_12_hour("fr-FR"), Some(false));
+    assert_eq!(prefers_12_hour("en_GB@euro"), Some(false));
+    assert_eq!(prefers_12_hour("C"), None);
+    assert_eq!(prefers_12_hour("POSIX"), None);
+}
+
+#[test]
+fn line_rendering() {
+    let plain = Style::new().force_styling(false);
+    assert_eq!(
+        render_line(
+            &format_duration(Duration::minutes(25)),
+            NaiveTime::from_hms(21, 0, 0),
+            "today",
+            &plain,
+            "%-I:%M%P",
+            0
+        ),
+        "00:25:00 until 9:00pm today"
+    );
+    assert_eq!(
+        render_line(
+            &format_duration(Duration::hours(14) + Duration::seconds(5)),
+            NaiveTime::from_hms(9, 30, 0),
+            "tomorrow",
+            &plain,
+            "%H:%M",
+            0
+        ),
+        "14:00:05 until 09:30 tomorrow"
+    );
+    assert_eq!(
+        render_line(
+            &format_duration(Duration::days(3) + Duration::hours(1)),
+            NaiveTime::from_hms(0, 0, 0),
+            "in 3 days",
+            &plain,
+            "%-I:%M%P",
+            0
+        ),
+        "3d 01:00:00 until 12:00am in 3 days"
+    );
+    // it was over a day a moment ago, so the rest of the line stays where it was
+    assert_eq!(
+        render_line(
+            &format_duration(Duration::hours(23) + Duration::minutes(59)),
+            NaiveTime::from_hms(0, 0, 0),
+            "tomorrow",
+            &plain,
+            "%-I:%M%P",
+            11
+        ),
+        "   23:59:00 until 12:00am tomorrow"
+    );
+}
+
+#[test]
+fn pomodoro_phases() {
+    let args = Cli::parse_from(["alrm", "--pomodoro", "--pomodoro-break", "10m"]);
+    assert_eq!(args.pomodoro_work, Duration::minutes(25));
+    assert_eq!(
+        Phase::Work.next(&args),
+        (Phase::Break, Duration::minutes(10))
+    );
+    assert_eq!(
+        Phase::Break.next(&args),
+        (Phase::Work, Duration::minutes(25))
+    );
+    assert!(Cli::try_parse_from(["alrm", "--pomodoro", "9am"]).is_err());
+    let err = Cli::try_parse_from(["alrm", "--pomodoro", "--pomodoro-work", "0s"]).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("DURATION must be longer than zero"));
+}
+
+#[test]
+fn rounding() {
+    let millis = Duration::milliseconds;
+    assert_eq!(Rounding::Floor.apply(millis(1999)), Duration::seconds(1));
+    assert_eq!(Rounding::Ceil.apply(millis(1001)), Duration::seconds(2));
+    assert_eq!(Rounding::Ceil.apply(millis(1000)), Duration::seconds(1));
+    assert_eq!(Rounding::Nearest.apply(millis(1499)), Duration::seconds(1));
+    assert_eq!(Rounding::Nearest.apply(millis(1500)), Duration::seconds(2));
+    // a countdown rounded up only shows zero once it's reached the time
+    assert_eq!(Rounding::Ceil.apply(millis(1)), Duration::seconds(1));
+    assert_eq!(Rounding::Ceil.apply(Duration::zero()), Duration::zero());
+    assert_eq!(Rounding::Floor.apply(millis(-1)), Duration::seconds(-1));
+    assert_eq!(Rounding::Ceil.apply(millis(-1999)), Duration::seconds(-1));
+}
+
+#[test]
+fn line_alignment() {
+    assert_eq!(
+        align_line("until 9:00pm today", "00:25:00", 30),
+        "until 9:00pm today    00:25:00"
+    );
+    let labeled = format!("{} until 9:00pm today", label("tea"));
+    assert_eq!(measure_text_width(&align_line(&labeled, "done", 40)), 40);
+    // too narrow to line up, but still readable
+    assert_eq!(
+        align_line("until 9:00pm today", "00:25:00", 10),
+        "until 9:00pm today 00:25:00"
+    );
+}
+
+#[test]
+fn color_choice() {
+    let args = Cli::parse_from(["alrm", "9am", "--color", "cyan"]);
+    assert_eq!(args.color, Some(CountdownColor::Cyan));
+    assert_eq!(
+        args.countdown_style(),
+        Style::new().bright().fg(Color::Cyan)
+    );
+    // yellow unless asked otherwise
+    assert_eq!(
+        Cli::parse_from(["alrm", "9am"]).countdown_style(),
+        Style::new().bright().yellow()
+    );
+    assert!(Cli::try_parse_from(["alrm", "9am", "--color", "purple"]).is_err());
+}
+
+#[test]
+fn second_alignment() {
+    let at = |nanos| Utc.ymd(2022, 3, 14).and_hms_nano(9, 0, 0, nanos);
+    assert_eq!(until_next_second(at(370_000_000)), 630);
+    // never waking up a fraction of a millisecond too early
+    assert_eq!(until_next_second(at(370_500_000)), 630);
+    assert_eq!(until_next_second(at(999_999_999)), 1);
+    // right on the second, the next one is a whole second away
+    assert_eq!(until_next_second(at(0)), 1000);
+    assert_eq!(until_next_second(at(1_250_000_000)), 750);
+}
+
+#[test]
+fn chimes() {
+    let start = Utc.ymd(2024, 3, 1).and_hms_milli(9, 7, 31, 250);
+    assert_eq!(
+        next_chime(Chime::Quarter, start),
+        Utc.ymd(2024, 3, 1).and_hms(9, 15, 0)
+    );
+    assert_eq!(
+        next_chime(Chime::Half, start),
+        Utc.ymd(2024, 3, 1).and_hms(9, 30, 0)
+    );
+    assert_eq!(
+        next_chime(Chime::Hour, start),
+        Utc.ymd(2024, 3, 1).and_hms(10, 0, 0)
+    );
+    // right on a chime, it's the next one
+    let on_the_hour = Utc.ymd(2024, 3, 1).and_hms(10, 0, 0);
+    assert_eq!(
+        next_chime(Chime::Hour, on_the_hour),
+        Utc.ymd(2024, 3, 1).and_hms(11, 0, 0)
+    );
+    assert_eq!(
+        next_chime(Chime::Quarter, Utc.ymd(2024, 3, 1).and_hms(23, 50, 0)),
+        Utc.ymd(2024, 3, 2).and_hms(0, 0, 0)
+    );
+
+    // going from chime to chime for a few days stays on the quarter hours
+    let mut chime = next_chime(Chime::Quarter, start);
+    for _ in 0..500 {
+        let next = next_chime(Chime::Quarter, chime);
+        assert_eq!(next - chime, Duration::minutes(15));
+        assert_eq!(
+            (next.minute() % 15, next.second(), next.nanosecond()),
+            (0, 0, 0)
+        );
+        chime = next;
+    }
+    assert_eq!(
+        chime,
+        start.date().and_hms(9, 0, 0) + Duration::minutes(15 * 501)
+    );
+}
+
+#[test]
+fn on_complete_parsing() {
+    assert_eq!(parse_on_complete("bell"), Ok(OnComplete::Bell));
+    #[cfg(feature = "notify")]
+    assert_eq!(parse_on_complete("notify"), Ok(OnComplete::Notify));
+    assert_eq!(
+        parse_on_complete("exec:mpv ding.mp3"),
+        Ok(OnComplete::Exec("mpv ding.mp3".to_string()))
+    );
+    // everything after the colon is the command, colons and all
+    assert_eq!(
+        parse_on_complete("exec:echo a:b"),
+        Ok(OnComplete::Exec("echo a:b".to_string()))
+    );
+    assert!(parse_on_complete("exec:").is_err());
+    assert!(parse_on_complete("exec:  ").is_err());
+    assert!(parse_on_complete("beep").is_err());
+    assert!(parse_on_complete("Exec:ls").is_err());
+}
+
+#[test]
+fn unit_formatting() {
+    assert_eq!(format_units(Duration::zero()), "0s");
+    assert_eq!(format_units(Duration::seconds(45)), "45s");
+    assert_eq!(format_units(Duration::seconds(200)), "3m 20s");
+    assert_eq!(format_units(Duration::hours(1)), "1h 0m 0s");
+    assert_eq!(format_units(Duration::hours(48)), "2d 0h 0m 0s");
+    assert_eq!(
+        format_units(Duration::days(3) + Duration::minutes(61) + Duration::seconds(5)),
+        "3d 1h 1m 5s"
+    );
+    assert_eq!(format_units(Duration::seconds(-200)), "-3m 20s");
+    // the time left is already rounded, so anything less than a second is gone
+    assert_eq!(format_units(Duration::milliseconds(999)), "0s");
+}
+
+#[test]
+fn repeating_until() {
+    let args = Cli::parse_from(["alrm", "--repeat-every", "20m", "--repeat-until", "5pm"]);
+    let now = pretend_now(NaiveTime::from_hms(9, 0, 0), Local.ymd(2024, 3, 1));
+    let until = opinionated_time_parsing_with(
+        args.repeat_until.as_deref().unwrap(),
+        args.parse_options(now.time()),
+    )
+    .unwrap();
+    let until = Some(resolve_target(until, now));
+    let at = |hour, minute| Local.ymd(2024, 3, 1).and_hms(hour, minute, 0);
+
+    let mut phase = Phase::Work;
+    assert_eq!(
+        after_finishing(&args, at(9, 20), 1, &mut phase, until),
+        Repeat::Next(at(9, 40))
+    );
+    // landing right on the time is still in time, but the one after that isn't
+    assert_eq!(
+        after_finishing(&args, at(16, 40), 23, &mut phase, until),
+        Repeat::Next(at(17, 0))
+    );
+    assert_eq!(
+        after_finishing(&args, at(17, 0), 24, &mut phase, until),
+        Repeat::Stopped
+    );
+    assert_eq!(
+        after_finishing(&args, at(17, 0), 24, &mut phase, None),
+        Repeat::Next(at(17, 20))
+    );
+
+    // going from one to the next like the main loop, whichever limit comes first wins
+    let finishes = |args: &Cli, until| {
+        let mut phase = Phase::Work;
+        let (mut date, mut finished) = (at(9, 20), 0);
+        loop {
+            finished += 1;
+            match after_finishing(args, date, finished, &mut phase, until) {
+                Repeat::Next(next) => date = next,
+                _ => return (finished, date),
+            }
+        }
+    };
+    assert_eq!(finishes(&args, until), (24, at(17, 0)));
+    let counted = Cli::parse_from(["alrm", "--repeat-every", "20m", "--repeat-count", "5"]);
+    assert_eq!(finishes(&counted, until), (5, at(10, 40)));
+    let counted = Cli::parse_from(["alrm", "--repeat-every", "20m", "--repeat-count", "50"]);
+    assert_eq!(finishes(&counted, None).0, 50);
+    assert_eq!(finishes(&counted, until), (24, at(17, 0)));
+
+    // a countdown that doesn't repeat just stops
+    let once = Cli::parse_from(["alrm", "9am"]);
+    assert_eq!(
+        after_finishing(&once, at(9, 0), 1, &mut phase, until),
+        Repeat::Stopped
+    );
+    // and a pomodoro goes back and forth
+    let pomodoro = Cli::parse_from(["alrm", "--pomodoro"]);
+    let mut phase = Phase::Work;
+    assert_eq!(
+        after_finishing(&pomodoro, at(9, 25), 1, &mut phase, until),
+        Repeat::Next(at(9, 30))
+    );
+    assert_eq!(phase, Phase::Break);
+
+    // a time that's already passed today is tomorrow, like any other
+    let until = resolve_target(NaiveTime::from_hms(8, 0, 0), now);
+    assert_eq!(until, Local.ymd(2024, 3, 2).and_hms(8, 0, 0));
+}
+
+#[test]
+fn negative_numbers_are_times() {
+    // they're bad times, but that's for the parser to explain rather than clap
+    assert_eq!(Cli::parse_from(["alrm", "-30"]).time, ["-30"]);
+    assert_eq!(Cli::parse_from(["alrm", "-u", "-0"]).time, ["-0"]);
+    assert!(Cli::parse_from(["alrm", "-30", "-u"]).update);
+    assert_eq!(Cli::parse_from(["alrm", "--", "-30m"]).time, ["-30m"]);
+}
+
+#[test]
+fn wrapped_lines() {
+    assert_eq!(physical_lines(27, 80), 1);
+    // right up to the edge still fits on one row
+    assert_eq!(physical_lines(80, 80), 1);
+    assert_eq!(physical_lines(81, 80), 2);
+    assert_eq!(physical_lines(27, 10), 3);
+    assert_eq!(physical_lines(30, 10), 3);
+    assert_eq!(physical_lines(0, 80), 1);
+    // a terminal that doesn't say how wide it is
+    assert_eq!(physical_lines(27, 0), 1);
+
+    // the colors don't take up any room
+    let line = render_line(
+        "00:25:00",
+        NaiveTime::from_hms(21, 0, 0),
+        "today",
+        &Style::new().yellow().force_styling(true),
+        "%-I:%M%P",
+        0,
+    );
+    assert_eq!(physical_lines(measure_text_width(&line), 20), 2);
+}
+
+#[test]
+fn wrapped_rows() {
+    let green = Style::new().green().force_styling(true);
+    let lines: Vec<_> = ["9:00am", "5:00pm - go home"]
+        .iter()
+        .map(|left| align_line(left, &green.apply_to("00:25:00").to_string(), 30))
+        .collect();
+    assert_eq!(physical_rows(&lines, 30), 2);
+    // too narrow for even one of them, so each one wraps onto a second row
+    assert_eq!(physical_rows(&lines, 20), 4);
+    assert_eq!(physical_rows(&lines, 10), 6);
+    assert_eq!(physical_rows(&[], 80), 0);
+}
+
+#[test]
+fn silent_past_time() {
+    // just after 9am, so 9am is nearly a day away
+    let left = Duration::hours(23) + Duration::minutes(59);
+    assert!(warns_of_rollover(
+        &Cli::parse_from(["alrm", "9am", "-u"]),
+        true,
+        left
+    ));
+    assert!(!warns_of_rollover(
+        &Cli::parse_from(["alrm", "9am", "--silent"]),
+        true,
+        left
+    ));
+    assert!(!warns_of_rollover(
+        &Cli::parse_from(["alrm", "9am", "--wait"]),
+        true,
+        left
+    ));
+    assert!(!warns_of_rollover(
+        &Cli::parse_from(["alrm", "9am"]),
+        true,
+        Duration::hours(2)
+    ));
 }
@@ -1,14 +1,17 @@
-use ariadne::{Cache, Color, Label, Report, ReportKind, Source};
-use chrono::{Duration, NaiveTime};
+use ariadne::{Cache, Color, Config, Label, Report, ReportKind, Source};
+use chrono::{
+    Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday,
+};
 use lazy_static::lazy_static;
 use range_check::{Check, OutOfRangeError};
-use regex::{Match, Regex};
+use regex::{Captures, Match, Regex};
 use std::error::Error;
 use std::fmt;
-use std::num::IntErrorKind;
+use std::num::{IntErrorKind, ParseIntError};
 use std::ops::Range;
 
-#[derive(Debug)]
+/// A part of the string that was being parsed, used to point out where an error is
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StringSection {
     text: String,
     start: usize,
@@ -16,7 +19,21 @@ pub struct StringSection {
 }
 
 impl StringSection {
-    fn new(s: &str, range: Range<usize>) -> Self {
+    /// The part of `s` in `range`, a range of byte offsets, for comparing against the section in
+    /// a `TimeParseError`
+    ///
+    /// ```
+    /// use alrm::{parse_target, Field, StringSection, TimeParseError};
+    ///
+    /// assert_eq!(
+    ///     parse_target("9:"),
+    ///     Err(TimeParseError::IncompleteField(
+    ///         Field::Minute,
+    ///         StringSection::new("9:", 2..2)
+    ///     ))
+    /// );
+    /// ```
+    pub fn new(s: &str, range: Range<usize>) -> Self {
         StringSection {
             text: s.into(),
             start: range.start,
@@ -24,18 +41,51 @@ impl StringSection {
         }
     }
 
-    fn range(&self) -> Range<usize> {
+    /// Where the section is in `text`, in bytes
+    pub fn range(&self) -> Range<usize> {
         self.start..self.end
     }
+
+    /// The whole string that the section is a part of
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Move a section of a substring of `s` starting at `offset` onto `s` itself
+    fn shifted(self, s: &str, offset: usize) -> Self {
+        StringSection::new(s, self.start + offset..self.end + offset)
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// A part of a time, like the one an error is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Field {
+    /// The time as a whole
     Overall,
+    /// The hour, e.g. the `9` in `9:30:15 pm`
     Hour,
+    /// The minute, e.g. the `30` in `9:30:15 pm`
     Minute,
+    /// The second, e.g. the `15` in `9:30:15 pm`
     Second,
+    /// The am/pm, e.g. the `pm` in `9:30:15 pm`
     Pm,
+    /// A word standing in for a time, e.g. `noon`
+    Keyword,
+    /// The date as a whole, e.g. the `2024-12-25` in `2024-12-25 9am`
+    Date,
+    /// The day of the month, e.g. the `25` in `2024-12-25` or `Dec 25`
+    Day,
+    /// The month, e.g. the `12` in `2024-12-25` or the `Dec` in `Dec 25`
+    Month,
+    /// The year, e.g. the `2024` in `2024-12-25`
+    Year,
+    /// The day of the week, e.g. the `monday` in `monday 9am`
+    Weekday,
+    /// The time zone, e.g. the `+05:30` in `9am +05:30`
+    TimeZone,
+    /// A Unix timestamp, e.g. the `1700000000` in `@1700000000`
+    Timestamp,
 }
 
 impl fmt::Display for Field {
@@ -49,28 +99,75 @@ impl fmt::Display for Field {
                 Self::Minute => "minute",
                 Self::Second => "second",
                 Self::Pm => "am/pm",
+                Self::Keyword => "keyword",
+                Self::Date => "date",
+                Self::Day => "day",
+                Self::Month => "month",
+                Self::Year => "year",
+                Self::Weekday => "weekday",
+                Self::TimeZone => "time zone",
+                Self::Timestamp => "timestamp",
             }
         )
     }
 }
 
-#[derive(Debug)]
+/// Why a time couldn't be parsed
+///
+/// The `Display` impl renders a report pointing out the problem in the original string
+#[derive(Debug, Clone, PartialEq)]
 pub enum TimeParseError {
+    /// A field was started but is missing its value, like the minutes in `9:`
     IncompleteField(Field, StringSection),
+    /// A field has a value that doesn't make sense for it, like the minutes in `9:75`
     OutOfRange(Field, StringSection, OutOfRangeError<u32>),
+    /// A field couldn't be understood at all
     InvalidFormat(Field, StringSection),
+    /// A field is a number too large to even check the range of, like `99999999999999999999`
+    TooLarge(Field, StringSection),
+    /// A 24-hour time was also given an am/pm, like `18:30 pm`
     Overconstrained {
+        /// The hour that is already 24-hour
         hour: StringSection,
+        /// The am/pm that shouldn't be there
         pm: StringSection,
     },
 }
 
 impl TimeParseError {
+    /// The field that couldn't be parsed
+    ///
+    /// For `Overconstrained`, this is the am/pm, since that is the part that shouldn't be there
+    pub fn field(&self) -> Field {
+        match self {
+            Self::IncompleteField(field, _) => *field,
+            Self::OutOfRange(field, _, _) => *field,
+            Self::InvalidFormat(field, _) => *field,
+            Self::TooLarge(field, _) => *field,
+            Self::Overconstrained { .. } => Field::Pm,
+        }
+    }
+
+    /// A short, stable name for the kind of error, for callers that want to branch on it
+    ///
+    /// One of `"incomplete"`, `"out_of_range"`, `"invalid_format"`, `"too_large"`, or
+    /// `"overconstrained"`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::IncompleteField(_, _) => "incomplete",
+            Self::OutOfRange(_, _, _) => "out_of_range",
+            Self::InvalidFormat(_, _) => "invalid_format",
+            Self::TooLarge(_, _) => "too_large",
+            Self::Overconstrained { .. } => "overconstrained",
+        }
+    }
+
     fn text(&self) -> &str {
         match self {
             Self::IncompleteField(_, section) => &section.text,
             Self::OutOfRange(_, section, _) => &section.text,
             Self::InvalidFormat(_, section) => &section.text,
+            Self::TooLarge(_, section) => &section.text,
             Self::Overconstrained { hour, pm: _ } => &hour.text,
         }
     }
@@ -80,9 +177,73 @@ impl TimeParseError {
             Self::IncompleteField(_, section) => section.start,
             Self::OutOfRange(_, section, _) => section.start,
             Self::InvalidFormat(_, section) => section.start,
+            Self::TooLarge(_, section) => section.start,
             Self::Overconstrained { hour: _, pm } => pm.start,
         }
     }
+
+    /// Move an error from parsing a substring of `s` starting at `offset` onto `s` itself
+    fn shifted(self, s: &str, offset: usize) -> Self {
+        match self {
+            Self::IncompleteField(field, section) => {
+                Self::IncompleteField(field, section.shifted(s, offset))
+            }
+            Self::OutOfRange(field, section, err) => {
+                Self::OutOfRange(field, section.shifted(s, offset), err)
+            }
+            Self::InvalidFormat(field, section) => {
+                Self::InvalidFormat(field, section.shifted(s, offset))
+            }
+            Self::TooLarge(field, section) => Self::TooLarge(field, section.shifted(s, offset)),
+            Self::Overconstrained { hour, pm } => Self::Overconstrained {
+                hour: hour.shifted(s, offset),
+                pm: pm.shifted(s, offset),
+            },
+        }
+    }
+}
+
+/// Suggest what a time like `18:30 pm` was meant to be, like "18:30 is already in the evening,
+/// so drop the "pm" or write 6:30pm"
+fn overconstrained_note(hour: &StringSection, pm: &StringSection) -> String {
+    let text = &hour.text;
+    // everything after the hour up to any am/pm, like the `:30` in `18:30 pm` or `pm 18:30`
+    let end = if pm.start >= hour.end {
+        pm.start
+    } else {
+        text.len()
+    };
+    let rest = text[hour.end..end].trim_end();
+    let pm_text = &text[pm.range()];
+    let value: u32 = text[hour.range()].parse().unwrap_or(0);
+    let time = format!("{}{}", &text[hour.range()], rest);
+    match value {
+        13..=17 => format!(
+            "{} is already in the afternoon, so drop the \"{}\" or write {}{}pm",
+            time,
+            pm_text,
+            value - 12,
+            rest
+        ),
+        18..=23 => format!(
+            "{} is already in the evening, so drop the \"{}\" or write {}{}pm",
+            time,
+            pm_text,
+            value - 12,
+            rest
+        ),
+        _ => format!("{} is already midnight, so drop the \"{}\"", time, pm_text),
+    }
+}
+
+/// What a mistyped am/pm, like `pn`, was probably meant to be. Anything further off than a
+/// single typo isn't worth guessing at
+fn pm_suggestions(pm: &str) -> Vec<&'static str> {
+    let pm = normalize_pm(pm);
+    ["am", "pm"]
+        .into_iter()
+        .filter(|suggestion| edit_distance(&pm, suggestion) == 1)
+        .collect()
 }
 
 struct StringSource(Source, String);
@@ -97,32 +258,59 @@ impl Cache<()> for StringSource {
     }
 }
 
-impl fmt::Display for TimeParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl TimeParseError {
+    /// The full report pointing at what went wrong, with or without colors
+    ///
+    /// `Display` shows the same report, colored if stderr can (or should) show colors
+    pub fn report(&self, color: bool) -> String {
         let mut buf = Vec::new();
-        let green = Color::Green.style().bold();
-        let builder = Report::build(ReportKind::Error, (), self.index());
+        let highlight = |c: Color| {
+            if color {
+                c.style().bold()
+            } else {
+                Color::Unset.style()
+            }
+        };
+        let green = highlight(Color::Green);
+        let builder = Report::build(ReportKind::Error, (), self.index())
+            .with_config(Config::default().with_color(color));
         match self {
             Self::IncompleteField(field, section) => {
                 if matches!(field, Field::Overall) {
                     builder.with_message("Expected time, instead got empty string")
                 } else {
+                    let label = Label::new(section.range())
+                        .with_message(format!("{} is missing", green.paint(field)));
+                    let label = if color {
+                        label.with_color(Color::Yellow)
+                    } else {
+                        label
+                    };
                     builder
                         .with_message(format!("{} field is incomplete", field))
-                        .with_label(
-                            Label::new(section.range())
-                                .with_message(format!("{} is missing", green.paint(field)))
-                                .with_color(Color::Yellow),
-                        )
+                        .with_label(label)
+                }
+            }
+            Self::OutOfRange(field, section, err) => {
+                let builder = builder
+                    .with_message(format!("{} field is out of range", field))
+                    .with_label(Label::new(section.range()).with_message(format!(
+                        "this is not in the proper range ({}) for {}",
+                        highlight(Color::White).paint(&err.allowed_range),
+                        green.paint(field)
+                    )));
+                if matches!(field, Field::Hour) {
+                    builder.with_note(match err.outside_value {
+                        13..=23 => "did you mean a 24-hour value, or to add am/pm?",
+                        24 => {
+                            "24 can only be used for the midnight at the end of the day, as 24:00"
+                        }
+                        _ => "there are only 24 hours in a day, so use 0-23, or 1-12 with am/pm",
+                    })
+                } else {
+                    builder
                 }
             }
-            Self::OutOfRange(field, section, err) => builder
-                .with_message(format!("{} field is out of range", field))
-                .with_label(Label::new(section.range()).with_message(format!(
-                    "this is not in the proper range ({}) for {}",
-                    Color::White.style().bold().paint(&err.allowed_range),
-                    green.paint(field)
-                ))),
             Self::InvalidFormat(field, section) => {
                 if matches!(field, Field::Overall) {
                     builder
@@ -132,22 +320,59 @@ impl fmt::Display for TimeParseError {
                             Label::new(section.range())
                                 .with_message("could not make sense of this"),
                         )
+                } else if matches!(field, Field::Keyword) {
+                    let keywords = KEYWORDS
+                        .iter()
+                        .map(|(keyword, _)| format!("`{}`", keyword))
+                        .collect::<Vec<_>>()
+                        .join(" or ");
+                    builder
+                        .with_message("Unknown keyword")
+                        .with_note(format!("expected {}", keywords))
+                        .with_label(
+                            Label::new(section.range())
+                                .with_message("could not make sense of this"),
+                        )
                 } else {
-                    builder.with_message("Invalid format").with_label(
+                    let builder = builder.with_message("Invalid format").with_label(
                         Label::new(section.range())
                             .with_message(format!("{} has invalid format", green.paint(field))),
-                    )
+                    );
+                    let text = &section.text[section.range()];
+                    let suggestions = pm_suggestions(text);
+                    let number = matches!(field, Field::Hour | Field::Minute | Field::Second);
+                    if matches!(field, Field::Pm) && !suggestions.is_empty() {
+                        let suggestions = suggestions
+                            .iter()
+                            .map(|pm| format!("'{}'", pm))
+                            .collect::<Vec<_>>()
+                            .join(" or ");
+                        builder.with_note(format!("did you mean {}?", suggestions))
+                    } else if number && text.starts_with('-') {
+                        // a unit right after the number, like the `m` in `-30m`, makes it a duration
+                        let unit =
+                            section.text[section.end..].starts_with(['h', 'm', 's', 'H', 'M', 'S']);
+                        builder.with_note(if unit {
+                            "durations can't be negative, since they're counted from now"
+                        } else {
+                            "times can't be negative; did you mean a duration?"
+                        })
+                    } else {
+                        builder
+                    }
                 }
             }
-            Self::Overconstrained { hour, pm } => {
-                builder
-                    .with_message("Time is overconstrained")
-                    .with_label(Label::new(hour.range()).with_message("this is already 24-hour"))
-                    .with_label(
-                        Label::new(pm.range()).with_message("so this is too much information"),
-                    )
-                //write!(f, "`{}` is already 24-hour, adding `{}` is too much information", hour.as_str(), pm.as_str())                }
-            }
+            Self::TooLarge(field, section) => builder
+                .with_message(format!("{} field is too large", field))
+                .with_label(Label::new(section.range()).with_message(format!(
+                    "this number is far too large for {}",
+                    green.paint(field)
+                ))),
+            Self::Overconstrained { hour, pm } => builder
+                .with_message("Time is overconstrained")
+                .with_label(Label::new(hour.range()).with_message("this is already 24-hour"))
+                .with_label(Label::new(pm.range()).with_message("so this is too much information"))
+                .with_note(overconstrained_note(hour, pm)),
         }
         .finish()
         .write(
@@ -155,7 +380,14 @@ impl fmt::Display for TimeParseError {
             &mut buf,
         )
         .unwrap();
-        write!(f, "{}", String::from_utf8(buf).unwrap())
+        String::from_utf8(buf).unwrap()
+    }
+}
+
+impl fmt::Display for TimeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // errors are printed to stderr, so follow whether it can (or should) show colors
+        write!(f, "{}", self.report(console::colors_enabled_stderr()))
     }
 }
 
@@ -168,206 +400,2527 @@ impl Error for TimeParseError {
     }
 }
 
+/// Ways to change how forgiving the parser is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Accept an am/pm that agrees with a 24-hour time, like `18:30 pm`, instead of calling it
+    /// `Overconstrained`. One that disagrees, like `18:30 am`, is still an error
+    pub lenient: bool,
+    /// The current time, used to fill in the hour and minute of times like `:45` or `:30:00`.
+    /// Without it, those are an error
+    pub now: Option<NaiveTime>,
+    /// Read a number on its own that's too big to be an hour, like `90`, as that many minutes
+    /// from now. Three or four digits are still military time, so `900` is 9:00 either way
+    pub minutes_if_big: bool,
+}
+
+/// A countdown target, as understood from the user's input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParsedTime {
+    /// A time of day, e.g. `9:30pm`
+    Absolute(NaiveTime),
+    /// An amount of time from now, e.g. `1h30m`
+    Relative(Duration),
+    /// A time of day on a specific date, e.g. `2024-12-25 9am`
+    DateTime(NaiveDateTime),
+    /// A time of day some number of days after today, e.g. `tomorrow 9am`
+    OnDay(u32, NaiveTime),
+    /// An exact moment in UTC, e.g. the Unix timestamp `@1700000000`
+    Instant(NaiveDateTime),
+    /// A time of day on a month and day, in whichever year that next comes around, e.g.
+    /// `Dec 25 9am`. The month and day are both counted from 1
+    OnDate(u32, u32, NaiveTime),
+    /// A time of day on the next of a day of the week, e.g. `monday 9am`
+    OnWeekday(Weekday, NaiveTime),
+}
+
 /**
- * We can parse
- * HH
- * HHp
- * HH p
- * HH:MM
- * HH:MMp
- * HH:MM p
- * HH:MM:SS
- * HH:MM:SSp
- * HH:MM:SS p
+ * Parse either a relative duration or a time of day, optionally on a specific date
  *
- * If the minutes or seconds are ommitted, they are assumed to be zero
- * If the am/pm is ommitted, it is interpeted as 24-hour time
+ * Dates are in ISO format (YYYY-MM-DD) and can be followed by a time of day
+ * (see `opinionated_time_parsing`). If the time is ommitted, it is assumed to be midnight
  *
- * All numeric fields can be zero-padded, or not
+ * A Unix timestamp (seconds since 1970-01-01 UTC) can be given after an `@`, like `@1700000000`
+ *
+ * A `+` in front always means a duration from now, like `+1h30m`. A plain number after it is
+ * minutes, so `+10` is 10 minutes from now where `10` would be 10:00
+ *
+ * `today` or `tomorrow` can go before a time in place of a date, like `tomorrow 9am`, to say
+ * which day it's on instead of it being the next time the clock reads that
+ *
+ * A day of the week can go there too, like `monday 9am` or `fri 5pm`, with its full name or its
+ * first three letters in any case. It's the next time it's that day and time, which is today
+ * if it's that day and the time hasn't passed yet
+ *
+ * A month and day can be written out, like `Dec 25` or `25 December`, with the month's full
+ * name or its first three letters in any case. It's the next time that date comes around, and
+ * can be followed by a time of day just like an ISO date
+ *
+ * With `options.minutes_if_big`, a number on its own is an hour up to 23, the end of the day at
+ * 24, and military time with three or four digits, like `0900`. Anything else, like `90` or
+ * `10000`, is that many minutes from now
+ *
+ * Durations are tried next (see `parse_relative_duration`), and anything that doesn't look
+ * like a duration is handed off to `opinionated_time_parsing`
  */
-pub fn opinionated_time_parsing(s: &str) -> Result<NaiveTime, TimeParseError> {
+pub fn parse_target(s: &str) -> Result<ParsedTime, TimeParseError> {
+    parse_target_with(s, ParseOptions::default())
+}
+
+/// Like `parse_target`, but with `options` for how strict to be
+pub fn parse_target_with(s: &str, options: ParseOptions) -> Result<ParsedTime, TimeParseError> {
     lazy_static! {
         static ref RE: Regex = Regex::new(
+            r"(?x)
+            ^
+            (?P<date>(?P<year>\d{4,})-(?P<month>\d{1,2})-(?P<day>\d{1,2})) # the date (required)
+            (?:\s+(?P<time>.*))? # the time of day (optional)
+            $
+        "
+        )
+        .unwrap();
+    }
+
+    if let Some(timestamp) = s.strip_prefix('@') {
+        let invalid =
+            || TimeParseError::InvalidFormat(Field::Timestamp, StringSection::new(s, 1..s.len()));
+        let seconds: i64 = timestamp
+            .parse()
+            .map_err(|err: ParseIntError| match err.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                    TimeParseError::TooLarge(Field::Timestamp, StringSection::new(s, 1..s.len()))
+                }
+                _ => invalid(),
+            })?;
+        // chrono can only represent a few hundred thousand years either side of 1970
+        return NaiveDateTime::from_timestamp_opt(seconds, 0)
+            .map(ParsedTime::Instant)
+            .ok_or_else(invalid);
+    }
+
+    // `+10` is always from now, unlike `10` which is a time of day
+    if let Some(duration) = s.strip_prefix('+') {
+        if duration.is_empty() {
+            return Err(TimeParseError::IncompleteField(
+                Field::Minute,
+                StringSection::new(s, 1..1),
+            ));
+        }
+        if duration.bytes().all(|c| c.is_ascii_digit()) {
+            return parse_minutes(s, 1..s.len()).map(ParsedTime::Relative);
+        }
+        return parse_relative_duration(duration)
+            .map(ParsedTime::Relative)
+            .map_err(|err| err.shifted(s, 1));
+    }
+
+    lazy_static! {
+        static ref DAY: Regex = Regex::new(
             r"(?xi)
-            (?P<hour>-?\d+)         # the hour (required)
-            (?::(?P<minute>-?\d*))? # the minute (optional)
-            (?::(?P<second>-?\d*))? # the second (optional)
-            (?:\s?(?P<pm>.*(?:am|pm)))? # am or pm (interpreted as 24-hour if ommitted)
+            ^
+            (?P<day>today|tomorrow) # the day (required)
+            (?:\s+(?P<time>.*))? # the time of day (optional)
+            $
         "
         )
         .unwrap();
     }
 
-    if s.is_empty() {
-        return Err(TimeParseError::IncompleteField(
-            Field::Overall,
-            StringSection::new(s, 0..s.len()),
-        ));
+    if let Some(cap) = captures(&DAY, s) {
+        let days = if cap["day"].eq_ignore_ascii_case("today") {
+            0
+        } else {
+            1
+        };
+        let (time, end_of_day) = match cap.name("time") {
+            None => (NaiveTime::from_hms(0, 0, 0), false),
+            Some(time) => parse_time_of_day(time.as_str(), options, false)
+                .map(|parsed| (parsed.time, parsed.end_of_day))
+                .map_err(|err| err.shifted(s, time.start()))?,
+        };
+
+        return Ok(ParsedTime::OnDay(days + u32::from(end_of_day), time));
     }
 
-    let cap = RE.captures(s).ok_or_else(|| {
-        TimeParseError::InvalidFormat(Field::Overall, StringSection::new(s, 0..s.len()))
-    })?;
+    lazy_static! {
+        static ref WEEKDAY: Regex = Regex::new(
+            r"(?xi)
+            ^
+            (?P<weekday>[a-z]+)\.? # the day of the week (required)
+            (?:\s+(?P<time>.*))? # the time of day (optional)
+            $
+        "
+        )
+        .unwrap();
+    }
 
-    // hour could be 24-hour but there's still an am/pm
+    if let Some(cap) = captures(&WEEKDAY, s) {
+        let name = cap.name("weekday").unwrap();
+        // plenty of times start with a word too, like `noon` or `pm 6`
+        let weekday = match weekday(name.as_str()) {
+            Some(weekday) => Some(weekday),
+            None if is_misspelled(name.as_str(), &WEEKDAYS) => {
+                return Err(TimeParseError::InvalidFormat(
+                    Field::Weekday,
+                    StringSection::new(s, name.range()),
+                ))
+            }
+            None => None,
+        };
+        if let Some(weekday) = weekday {
+            let (time, end_of_day) = match cap.name("time") {
+                None => (NaiveTime::from_hms(0, 0, 0), false),
+                Some(time) => parse_time_of_day(time.as_str(), options, false)
+                    .map(|parsed| (parsed.time, parsed.end_of_day))
+                    .map_err(|err| err.shifted(s, time.start()))?,
+            };
+            let weekday = if end_of_day { weekday.succ() } else { weekday };
 
-    let hour = match cap.name("hour") {
-        None => {
-            return Err(TimeParseError::IncompleteField(
-                Field::Hour,
-                StringSection::new(s, 0..s.len()),
-            ))
+            return Ok(ParsedTime::OnWeekday(weekday, time));
         }
-        Some(capture) => parse_field(s, Field::Hour, 0..24, capture)?,
-    };
-    let minute = match cap.name("minute") {
-        None => 0,
-        Some(capture) => parse_field(s, Field::Minute, 0..60, capture)?,
-    };
-    let second = match cap.name("second") {
-        None => 0,
-        Some(capture) => parse_field(s, Field::Second, 0..60, capture)?,
-    };
+    }
 
-    let pm = match cap.name("pm") {
-        None => None,
-        Some(pm) => Some(match pm.as_str().to_ascii_lowercase().as_str() {
-            "am" => Duration::zero(),
-            "pm" => {
-                if hour == 12 {
-                    // 12 pm is already correct
-                    // we don't need to do anything to convert to 24-hour time
-                    Duration::zero()
-                } else {
-                    Duration::hours(12)
-                }
-            }
-            _ => {
+    lazy_static! {
+        static ref MONTH_DAY: Regex = Regex::new(
+            r"(?xi)
+            ^
+            (?P<date>
+                (?P<month>[a-z]+)\.?\s+(?P<day>\d{1,2})(?:st|nd|rd|th)? # like `Dec 25`
+            |
+                (?P<day_first>\d{1,2})(?:st|nd|rd|th)?\s+(?P<month_last>[a-z]+)\.? # like `25 Dec`
+            )
+            (?:\s+(?P<time>.*))? # the time of day (optional)
+            $
+        "
+        )
+        .unwrap();
+    }
+
+    if let Some(cap) = captures(&MONTH_DAY, s) {
+        let month_match = cap
+            .name("month")
+            .or_else(|| cap.name("month_last"))
+            .unwrap();
+        let day = cap.name("day").or_else(|| cap.name("day_first")).unwrap();
+        // plenty of other things look like this, like `6 pm`, so it's only a date if there's a
+        // month in it, or something that was almost certainly meant to be one
+        let month = match month_number(month_match.as_str()) {
+            Some(month) => Some(month),
+            None if is_misspelled(month_match.as_str(), &MONTHS) => {
                 return Err(TimeParseError::InvalidFormat(
-                    Field::Pm,
-                    StringSection::new(s, pm.range()),
+                    Field::Month,
+                    StringSection::new(s, month_match.range()),
                 ))
             }
-        }),
-    };
+            None => None,
+        };
+        if let Some(month) = month {
+            // a leap year, so that Feb 29 is a real date
+            let date = parse_date(s, 2000, month, day)?;
 
-    if hour > 12 && pm.is_some() {
-        return Err(TimeParseError::Overconstrained {
-            hour: StringSection::new(s, cap.name("hour").unwrap().range()),
-            pm: StringSection::new(s, cap.name("pm").unwrap().range()),
-        });
+            let (time, end_of_day) = match cap.name("time") {
+                None => (NaiveTime::from_hms(0, 0, 0), false),
+                Some(time) => parse_time_of_day(time.as_str(), options, false)
+                    .map(|parsed| (parsed.time, parsed.end_of_day))
+                    .map_err(|err| err.shifted(s, time.start()))?,
+            };
+            let date_range = cap.name("date").unwrap().range();
+            let date = if end_of_day {
+                day_after(s, date, date_range)?
+            } else {
+                date
+            };
+
+            return Ok(ParsedTime::OnDate(date.month(), date.day(), time));
+        }
     }
 
-    let mut time = NaiveTime::from_hms_opt(hour, minute, second).unwrap();
+    if let Some(cap) = captures(&RE, s) {
+        let year = parse_field(s, Field::Year, 0..MAX_YEAR + 1, cap.name("year").unwrap())?;
+        let month = parse_field(s, Field::Month, 1..13, cap.name("month").unwrap())?;
+        let date = parse_date(s, year as i32, month, cap.name("day").unwrap())?;
 
-    if let Some(diff) = pm {
-        time += diff;
+        let (time, end_of_day) = match cap.name("time") {
+            None => (NaiveTime::from_hms(0, 0, 0), false),
+            Some(time) => parse_time_of_day(time.as_str(), options, false)
+                .map(|parsed| (parsed.time, parsed.end_of_day))
+                .map_err(|err| err.shifted(s, time.start()))?,
+        };
+        let date = if end_of_day {
+            day_after(s, date, cap.name("year").unwrap().range())?
+        } else {
+            date
+        };
+
+        return Ok(ParsedTime::DateTime(date.and_time(time)));
+    }
+
+    let digits = !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit());
+    if options.minutes_if_big && digits && !(3..=4).contains(&s.len()) {
+        match s.parse::<u64>() {
+            Ok(hour) if hour <= 24 => {}
+            // it's all digits, so if it didn't parse it's far too many minutes
+            _ => return parse_minutes(s, 0..s.len()).map(ParsedTime::Relative),
+        }
+    }
+
+    match relative_duration(s) {
+        Some(duration) => duration.map(ParsedTime::Relative),
+        None => {
+            let parsed = parse_time_of_day(s, options, false)?;
+            if parsed.end_of_day {
+                // the midnight at the end of today is always the one starting tomorrow
+                Ok(ParsedTime::OnDay(1, parsed.time))
+            } else {
+                Ok(ParsedTime::Absolute(parsed.time))
+            }
+        }
     }
+}
 
-    Ok(time)
+/// A number of minutes in the `digits` of `s`, as a duration. More than can be counted down to
+/// is `TooLarge`
+fn parse_minutes(s: &str, digits: Range<usize>) -> Result<Duration, TimeParseError> {
+    s[digits.clone()]
+        .parse::<u64>()
+        .ok()
+        .and_then(|minutes| minutes.checked_mul(60))
+        .and_then(|seconds| Duration::from_std(std::time::Duration::from_secs(seconds)).ok())
+        .ok_or_else(|| TimeParseError::TooLarge(Field::Minute, StringSection::new(s, digits)))
 }
 
-fn parse_field(
+/// The names of the months, in order
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Which month `name` is, counting from 1, if it's a month's full name or its first three letters
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.to_ascii_lowercase();
+    MONTHS
+        .iter()
+        .position(|month| name == *month || name == month[..3])
+        .map(|index| index as u32 + 1)
+}
+
+/// The latest year a date can be in. chrono goes much further, but a countdown to anything near
+/// the end of what it can represent overflows as soon as it's worked out
+const MAX_YEAR: u32 = 9999;
+
+/// The day after `date`, for the midnight at the end of it. If there isn't one, the year in the
+/// `section` of `s` is out of range
+fn day_after(s: &str, date: NaiveDate, section: Range<usize>) -> Result<NaiveDate, TimeParseError> {
+    date.succ_opt().ok_or_else(|| {
+        TimeParseError::OutOfRange(
+            Field::Year,
+            StringSection::new(s, section),
+            (date.year() as u32 + 1)
+                .check_range(0..MAX_YEAR + 1)
+                .unwrap_err(),
+        )
+    })
+}
+
+/// The `day` of `month` in `year`, as long as that month has that many days
+fn parse_date(s: &str, year: i32, month: u32, day: Match) -> Result<NaiveDate, TimeParseError> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    // the last day of the month is the day before the next one starts
+    let days =
+        NaiveDate::from_ymd_opt(next_year, next_month, 1).map_or(31, |next| next.pred().day());
+    let day = parse_field(s, Field::Day, 1..days + 1, day)?;
+    Ok(NaiveDate::from_ymd(year, month, day))
+}
+
+/// The names of the days of the week, starting from Monday
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Which day of the week `name` is, if it's a day's full name or its first three letters
+fn weekday(name: &str) -> Option<Weekday> {
+    let name = name.to_ascii_lowercase();
+    let index = WEEKDAYS
+        .iter()
+        .position(|weekday| name == *weekday || name == weekday[..3])?;
+    // there's no way to go from a number to a `Weekday` except by counting
+    Some((0..index).fold(Weekday::Mon, |weekday, _| weekday.succ()))
+}
+
+/// Whether `name` is one typo away from one of the full `names`, like `Decmber` for a month.
+/// The short names are too close to other words to guess at
+fn is_misspelled(name: &str, names: &[&str]) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.len() > 3 && names.iter().any(|full| edit_distance(&name, full) == 1)
+}
+
+/// The time zone that a time was given in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zone {
+    /// A fixed offset from UTC, e.g. `UTC` or `+05:30`
+    Fixed(FixedOffset),
+    /// A time zone from the tz database, e.g. `America/New_York`, or an abbreviation standing in for one, e.g. `EST`
+    #[cfg(feature = "tz")]
+    Named(chrono_tz::Tz),
+}
+
+/// Common time zone abbreviations, and the zone people usually mean by them
+///
+/// These follow daylight saving time, so `EST` in July means Eastern time rather than UTC-5
+#[cfg(feature = "tz")]
+const ZONE_ABBREVIATIONS: [(&str, chrono_tz::Tz); 20] = {
+    use chrono_tz::{America, Asia, Australia, Europe, Pacific};
+    [
+        ("ET", America::New_York),
+        ("EST", America::New_York),
+        ("EDT", America::New_York),
+        ("CT", America::Chicago),
+        ("CST", America::Chicago),
+        ("CDT", America::Chicago),
+        ("MT", America::Denver),
+        ("MST", America::Denver),
+        ("MDT", America::Denver),
+        ("PT", America::Los_Angeles),
+        ("PST", America::Los_Angeles),
+        ("PDT", America::Los_Angeles),
+        ("HST", Pacific::Honolulu),
+        ("BST", Europe::London),
+        ("CET", Europe::Paris),
+        ("CEST", Europe::Paris),
+        ("IST", Asia::Kolkata),
+        ("JST", Asia::Tokyo),
+        ("AEST", Australia::Sydney),
+        ("AEDT", Australia::Sydney),
+    ]
+};
+
+/**
+ * Parse a target like `parse_target`, optionally followed by the time zone it's in
+ *
+ * We can parse zones like
+ * UTC
+ * GMT
+ * +HH
+ * +HH:MM
+ * +HHMM
+ * UTC+HH:MM
+ *
+ * and, with the `tz` feature, names from the tz database like `America/New_York`
+ * and common abbreviations like `EST` or `PT`
+ *
+ * The zone must be separated from the time by a space
+ */
+pub fn parse_zoned_target(s: &str) -> Result<(ParsedTime, Option<Zone>), TimeParseError> {
+    parse_zoned_target_with(s, ParseOptions::default())
+}
+
+/// Like `parse_zoned_target`, but with `options` for how strict to be
+pub fn parse_zoned_target_with(
     s: &str,
-    field: Field,
-    range: Range<u32>,
-    capture: Match,
-) -> Result<u32, TimeParseError> {
-    capture
-        .as_str()
-        .parse::<u32>()
-        .map_err(|err| match err.kind() {
-            IntErrorKind::Empty => {
-                TimeParseError::IncompleteField(field, StringSection::new(s, capture.range()))
-            }
-            IntErrorKind::InvalidDigit => {
-                TimeParseError::InvalidFormat(field, StringSection::new(s, capture.range()))
-            }
-            _ => TimeParseError::InvalidFormat(field, StringSection::new(s, capture.range())),
-        })?
-        .check_range(range)
-        .map_err(|err| {
-            TimeParseError::OutOfRange(field, StringSection::new(s, capture.range()), err)
-        })
+    options: ParseOptions,
+) -> Result<(ParsedTime, Option<Zone>), TimeParseError> {
+    if let Some((rest, zone)) = s.trim_end().rsplit_once(char::is_whitespace) {
+        if let Some(zone) = parse_zone(zone).map_err(|err| err.shifted(s, rest.len() + 1))? {
+            let target =
+                parse_target_with(rest.trim_end(), options).map_err(|err| err.shifted(s, 0))?;
+            return Ok((target, Some(zone)));
+        }
+    }
+
+    parse_target_with(s, options).map(|target| (target, None))
 }
 
-#[test]
-fn time_parsing_happy_paths() {
-    assert_eq!(
-        opinionated_time_parsing("6").unwrap(),
-        NaiveTime::from_hms(6, 0, 0)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6am").unwrap(),
-        NaiveTime::from_hms(6, 0, 0)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6pm").unwrap(),
-        NaiveTime::from_hms(18, 0, 0)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6 pm").unwrap(),
-        NaiveTime::from_hms(18, 0, 0)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6:30").unwrap(),
-        NaiveTime::from_hms(6, 30, 0)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6:30pm").unwrap(),
-        NaiveTime::from_hms(18, 30, 0)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6:30 pm").unwrap(),
-        NaiveTime::from_hms(18, 30, 0)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6:30:15").unwrap(),
-        NaiveTime::from_hms(6, 30, 15)
-    );
-    assert_eq!(
-        opinionated_time_parsing("6:30:15 pm").unwrap(),
-        NaiveTime::from_hms(18, 30, 15)
-    );
+/**
+ * Parse one or more targets like `parse_zoned_target`
+ *
+ * If `s` isn't a single target, it is split up on whitespace and commas and each part is
+ * parsed on its own, so `9am 12pm 3pm` and `9am,12pm,3pm` both give three targets.
+ * The targets are returned in the order they were given
+ */
+pub fn parse_zoned_targets(s: &str) -> Result<Vec<(ParsedTime, Option<Zone>)>, TimeParseError> {
+    parse_zoned_targets_with(s, ParseOptions::default())
 }
 
-#[test]
-fn time_parsing_edge_cases() {
-    println!(
-        "{}",
-        opinionated_time_parsing("").expect_err("test string is empty")
-    );
-    println!(
-        "{}",
-        opinionated_time_parsing("18:30 pm").expect_err("test string is overconstrained")
-    );
-    println!(
-        "{}",
-        opinionated_time_parsing("6:306").expect_err("minutes are out of bounds")
-    );
-    println!(
-        "{}",
-        opinionated_time_parsing("6::6").expect_err("minutes are missing")
-    );
-    println!(
+/// Like `parse_zoned_targets`, but with `options` for how strict to be
+pub fn parse_zoned_targets_with(
+    s: &str,
+    options: ParseOptions,
+) -> Result<Vec<(ParsedTime, Option<Zone>)>, TimeParseError> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"[^,\s]+").unwrap();
+    }
+
+    let whole_err = match parse_zoned_target_with(s, options) {
+        Ok(target) => return Ok(vec![target]),
+        Err(err) => err,
+    };
+
+    let parts: Vec<_> = RE
+        .find_iter(s)
+        .map(|part| {
+            (
+                part.start(),
+                part.as_str(),
+                parse_zoned_target_with(part.as_str(), options),
+            )
+        })
+        .collect();
+
+    // if none of the parts make sense, or one of them doesn't look like a target on its own
+    // (like the `pm` in `18:30 pm`, the `:` in `9 :`, or anything that's only partly there),
+    // then `s` was probably meant to be a single target
+    let standalone = |text: &str, part: &Result<_, TimeParseError>| {
+        text.chars().any(char::is_alphanumeric)
+            && !matches!(
+                part,
+                Err(TimeParseError::InvalidFormat(Field::Overall, _)
+                    | TimeParseError::IncompleteField(_, _))
+            )
+    };
+    if parts.len() < 2
+        || parts.iter().all(|(_, _, part)| part.is_err())
+        || !parts.iter().all(|(_, text, part)| standalone(text, part))
+    {
+        return Err(whole_err);
+    }
+
+    // only move the error that's returned onto `s`, since that copies all of `s`
+    parts
+        .into_iter()
+        .map(|(start, _, part)| part.map_err(|err| err.shifted(s, start)))
+        .collect()
+}
+
+/// Parse `s` as a time zone, or `None` if it doesn't look like one
+fn parse_zone(s: &str) -> Result<Option<Zone>, TimeParseError> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(?xi)
+            ^
+            (?:utc|gmt)?         # the zone the offset is from (optional)
+            (?P<sign>[+-])       # the direction of the offset (required)
+            (?P<hour>\d{1,2})    # the hours (required)
+            (?::?(?P<minute>\d{2}))? # the minutes (optional)
+            $
+        "
+        )
+        .unwrap();
+    }
+
+    if s.eq_ignore_ascii_case("utc") || s.eq_ignore_ascii_case("gmt") {
+        return Ok(Some(Zone::Fixed(FixedOffset::east(0))));
+    }
+
+    if let Some(cap) = captures(&RE, s) {
+        let hour = parse_field(s, Field::TimeZone, 0..24, cap.name("hour").unwrap())?;
+        let minute = match cap.name("minute") {
+            None => 0,
+            Some(capture) => parse_field(s, Field::TimeZone, 0..60, capture)?,
+        };
+        let mut offset = (hour * 60 * 60 + minute * 60) as i32;
+        if &cap["sign"] == "-" {
+            offset = -offset;
+        }
+        return Ok(Some(Zone::Fixed(FixedOffset::east(offset))));
+    }
+
+    #[cfg(feature = "tz")]
+    {
+        if let Some((_, zone)) = ZONE_ABBREVIATIONS
+            .iter()
+            .find(|(abbreviation, _)| s.eq_ignore_ascii_case(abbreviation))
+        {
+            return Ok(Some(Zone::Named(*zone)));
+        }
+        if let Ok(zone) = s.parse() {
+            return Ok(Some(Zone::Named(zone)));
+        }
+    }
+
+    Ok(None)
+}
+
+/**
+ * We can parse durations like
+ * 30m
+ * 90s
+ * 1h30m
+ * 1h30m15s
+ *
+ * Units must be given in order (hours, minutes, seconds) and any unit can be ommitted.
+ * The first unit given can be as large as you like, but the units following it must be
+ * less than 60
+ *
+ * ISO 8601 durations like `PT1H30M` or `P1DT12H` work too, as long as they stick to days,
+ * hours, minutes, and seconds
+ */
+pub fn parse_relative_duration(s: &str) -> Result<Duration, TimeParseError> {
+    relative_duration(s).unwrap_or_else(|| {
+        Err(TimeParseError::InvalidFormat(
+            Field::Overall,
+            StringSection::new(s, 0..s.len()),
+        ))
+    })
+}
+
+/// Parse a duration like `parse_relative_duration`, or give `None` if `s` doesn't look like one
+/// at all, without making an error for it
+fn relative_duration(s: &str) -> Option<Result<Duration, TimeParseError>> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(?xi)
+            ^
+            (?:(?P<hour>-?\d*)h)?   # hours (optional)
+            (?:(?P<minute>-?\d*)m)? # minutes (optional)
+            (?:(?P<second>-?\d*)s)? # seconds (optional)
+            $
+        "
+        )
+        .unwrap();
+    }
+
+    if s.is_empty() {
+        return Some(Err(TimeParseError::IncompleteField(
+            Field::Overall,
+            StringSection::new(s, 0..s.len()),
+        )));
+    }
+
+    if s.starts_with('P') {
+        return parse_iso_duration(s);
+    }
+
+    let cap = captures(&RE, s)?;
+    Some(sum_duration(s, &cap))
+}
+
+/// Add up the units `relative_duration` found
+fn sum_duration(s: &str, cap: &Captures) -> Result<Duration, TimeParseError> {
+    let mut duration = Duration::zero();
+    // only the leading unit is allowed to overflow into the next one, so `90m` is fine but `1h90m` is not
+    let mut range = 0..u32::MAX;
+    for (name, field, unit) in [
+        ("hour", Field::Hour, 60 * 60),
+        ("minute", Field::Minute, 60),
+        ("second", Field::Second, 1),
+    ] {
+        if let Some(capture) = cap.name(name) {
+            let value = parse_field(s, field, range, capture)?;
+            duration = duration + Duration::seconds(i64::from(value) * unit);
+            range = 0..60;
+        }
+    }
+
+    Ok(duration)
+}
+
+/// Parse an ISO 8601 duration, like `PT1H30M`. Unlike `1h30m`, every unit can be as large as
+/// you like, so `PT90M` is fine
+fn parse_iso_duration(s: &str) -> Option<Result<Duration, TimeParseError>> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(?x)
+            ^P
+            (?:(?P<day>\d+)D)?             # days (optional)
+            (?:T
+                (?:(?P<hour>\d+)H)?        # hours (optional)
+                (?:(?P<minute>\d+)M)?      # minutes (optional)
+                (?:(?P<second>\d+)S)?      # seconds (optional)
+            )?
+            $
+        "
+        )
+        .unwrap();
+    }
+
+    let cap = captures(&RE, s)?;
+    Some(sum_iso_duration(s, &cap))
+}
+
+/// Add up the units `parse_iso_duration` found
+fn sum_iso_duration(s: &str, cap: &Captures) -> Result<Duration, TimeParseError> {
+    let mut duration = Duration::zero();
+    let mut any = false;
+    for (name, field, unit) in [
+        ("day", Field::Day, 24 * 60 * 60),
+        ("hour", Field::Hour, 60 * 60),
+        ("minute", Field::Minute, 60),
+        ("second", Field::Second, 1),
+    ] {
+        if let Some(capture) = cap.name(name) {
+            let value = parse_field(s, field, 0..u32::MAX, capture)?;
+            duration = duration + Duration::seconds(i64::from(value) * unit);
+            any = true;
+        }
+    }
+
+    // `P` and `PT` on their own don't say how long to wait
+    if !any {
+        return Err(TimeParseError::IncompleteField(
+            Field::Hour,
+            StringSection::new(s, s.len()..s.len()),
+        ));
+    }
+
+    Ok(duration)
+}
+
+/// Words that stand in for a time of day, along with the hour they mean
+const KEYWORDS: [(&str, u32); 2] = [("noon", 12), ("midnight", 0)];
+
+/// The time of day patterns `opinionated_time_parsing` understands, each with an example
+pub const FORMATS: [(&str, &str); 22] = [
+    ("noon", "noon"),
+    ("midnight", "midnight"),
+    ("HH", "21"),
+    ("HHp", "9pm"),
+    ("HH p", "9 pm"),
+    ("HH:MM", "21:30"),
+    ("HH:MMp", "9:30pm"),
+    ("HH:MM p", "9:30 p.m."),
+    ("HH:MM:SS", "21:30:15"),
+    ("HH:MM:SSp", "9:30:15pm"),
+    ("HH:MM:SS p", "9:30:15 pm"),
+    ("HH.frac", "21.5"),
+    ("HH.fracp", "9.5pm"),
+    ("HH.frac p", "9.5 pm"),
+    ("HH,MM", "21,30"),
+    ("HHMM", "2130"),
+    ("p HH:MM", "pm 9:30"),
+    ("HH o'clock", "9 o'clock"),
+    ("HH o'clock p", "9 o'clock pm"),
+    ("half past HH", "half past 9"),
+    ("quarter past HH p", "quarter past 6 pm"),
+    ("quarter to HH", "quarter to 10"),
+];
+
+/**
+ * We can parse any of the patterns in `FORMATS`
+ *
+ * Whitespace around the time is ignored, and nothing but whitespace is as empty as `""`
+ * Colons can have spaces around them, like `9 : 30`
+ * The fields can be separated by commas instead of colons, like `6,30`, as long as they all are
+ * Three or four digits on their own are military time, so `900` is 9:00 and `2130` is 21:30
+ * A fraction of an hour is rounded to the nearest minute, so `6.5` is 6:30 and `6.25` is 6:15
+ * If the minutes or seconds are ommitted, they are assumed to be zero
+ * Empty minutes between two colons, like `HH::SS`, are assumed to be zero too,
+ * but a trailing colon with nothing after it, like `HH:` or `HH:MM:`, is an error
+ * If the am/pm is ommitted, it is interpeted as 24-hour time
+ * The am/pm can be written with periods and spaces, like `a.m.` or `p. m.`, or shortened to `a` or `p`
+ * The am/pm can also go before the time, like `pm 6`, as long as it isn't shortened
+ * `half past`, `quarter past`, and `quarter to` can go before an hour, so `quarter to 10` is 9:45
+ * `:SS` and `:MM:SS` leave off the leading fields, which are filled in from `options.now`
+ * `o'clock` can also be written `oclock`, but it can't be used along with minutes or seconds
+ * `0`, `00`, `0:00`, and `0000` are all the midnight at the start of the day, like `midnight`
+ * and `12am`. That has nearly always passed, so counting down to it goes to the one tomorrow
+ * `24:00` (or `24`, or `2400`) is midnight at the end of the day. It's 00:00 here, but
+ * `parse_target` counts down to the midnight starting tomorrow. `24:01` and later are errors
+ *
+ * All numeric fields can be zero-padded, or not
+ * Keywords are case-insensitive
+ */
+pub fn opinionated_time_parsing(s: &str) -> Result<NaiveTime, TimeParseError> {
+    opinionated_time_parsing_with(s, ParseOptions::default())
+}
+
+/// Like `opinionated_time_parsing`, but with `options` for how strict to be
+pub fn opinionated_time_parsing_with(
+    s: &str,
+    options: ParseOptions,
+) -> Result<NaiveTime, TimeParseError> {
+    parse_time_of_day(s, options, false).map(|parsed| parsed.time)
+}
+
+/**
+ * Like `opinionated_time_parsing`, but also gives where each field was found in `s`, so the
+ * parts of a time can be picked out, like for highlighting them in an editor
+ *
+ * The spans are byte ranges into `s`, in the order they appear. A keyword like `noon` is a
+ * single `Field::Keyword`, and the `quarter past` in `quarter past 6` is the `Field::Minute`
+ */
+pub fn parse_with_spans(s: &str) -> Result<(NaiveTime, FieldSpans), TimeParseError> {
+    parse_time_of_day(s, ParseOptions::default(), true).map(|parsed| (parsed.time, parsed.spans))
+}
+
+/// Where each field of a time was found in the string it was parsed from, as byte ranges
+pub type FieldSpans = Vec<(Field, Range<usize>)>;
+
+/// A time of day, along with how it was written
+struct TimeOfDay {
+    time: NaiveTime,
+    /// Whether it was `24:00`, which is the midnight at the end of the day rather than the one
+    /// at the start of it
+    end_of_day: bool,
+    /// Where each field was found in the string, in order, if they were asked for
+    spans: FieldSpans,
+}
+
+/// Parse a time of day like `opinionated_time_parsing_with`, keeping track of how it was written.
+/// Finding the spans is left out unless `with_spans` is set, since it means allocating
+fn parse_time_of_day(
+    s: &str,
+    options: ParseOptions,
+    with_spans: bool,
+) -> Result<TimeOfDay, TimeParseError> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(?xi)
+            ^\s*
+            (?:(?P<leading_pm>[ap]\.?\s*m\.?)\s+)? # am or pm before the time (optional)
+            (?P<hour>-?\d+)         # the hour (required)
+            (?:
+                \.(?P<fraction>\d*)  # a fraction of an hour (optional)
+            |
+                (?:(?P<minute_sep>\s*:\s*|,)(?P<minute>-?\d*))? # the minute (optional)
+                (?:(?P<second_sep>\s*:\s*|,)(?P<second>-?\d*))? # the second (optional)
+            )
+            (?:\s*(?P<oclock>o'?clock))? # o'clock (optional)
+            (?:\s?(?P<pm>.*[a-z]\.?\s*m\.?|[a-z]\.?))? # am or pm (interpreted as 24-hour if ommitted)
+        "
+        )
+        .unwrap();
+    }
+
+    // nothing but whitespace is just as empty as nothing at all
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        return Err(TimeParseError::IncompleteField(
+            Field::Overall,
+            StringSection::new(s, 0..s.len()),
+        ));
+    }
+    if trimmed.len() != s.len() {
+        let start = s.len() - s.trim_start().len();
+        let parsed =
+            parse_time_of_day(trimmed, options, with_spans).map_err(|err| err.shifted(s, start))?;
+        let spans = parsed
+            .spans
+            .into_iter()
+            .map(|(field, range)| (field, range.start + start..range.end + start))
+            .collect();
+        return Ok(TimeOfDay { spans, ..parsed });
+    }
+
+    let without_end_of_day = |(time, spans)| TimeOfDay {
+        time,
+        end_of_day: false,
+        spans,
+    };
+
+    for (keyword, hour) in KEYWORDS {
+        if s.eq_ignore_ascii_case(keyword) {
+            let spans = if with_spans {
+                vec![(Field::Keyword, 0..s.len())]
+            } else {
+                Vec::new()
+            };
+            return Ok(without_end_of_day((NaiveTime::from_hms(hour, 0, 0), spans)));
+        }
+    }
+
+    if let Some(time) = parse_phrase(s, options, with_spans) {
+        return time.map(without_end_of_day);
+    }
+
+    if let Some(time) = parse_partial(s, options, with_spans) {
+        return time.map(without_end_of_day);
+    }
+
+    // something like `noom` was probably meant to be a keyword
+    if s.chars().all(|c| c.is_ascii_alphabetic())
+        && KEYWORDS
+            .iter()
+            .any(|(keyword, _)| edit_distance(&s.to_ascii_lowercase(), keyword) <= 2)
+    {
+        return Err(TimeParseError::InvalidFormat(
+            Field::Keyword,
+            StringSection::new(s, 0..s.len()),
+        ));
+    }
+
+    let cap = captures(&RE, s).ok_or_else(|| {
+        TimeParseError::InvalidFormat(Field::Overall, StringSection::new(s, 0..s.len()))
+    })?;
+
+    // everything after the time should just be whitespace
+    let rest = &s[cap.get(0).unwrap().end()..];
+    if !rest.trim().is_empty() {
+        let start = s.len() - rest.trim_start().len();
+        // `6 pn` only gets as far as the `p`, but it's most likely a typo for pm
+        let suffix = cap.name("pm").map_or(start, |pm| pm.start())..s.trim_end().len();
+        if !pm_suggestions(&s[suffix.clone()]).is_empty() {
+            return Err(TimeParseError::InvalidFormat(
+                Field::Pm,
+                StringSection::new(s, suffix),
+            ));
+        }
+        return Err(TimeParseError::InvalidFormat(
+            Field::Overall,
+            StringSection::new(s, start..s.trim_end().len()),
+        ));
+    }
+
+    // `pm 6` is the same as `6 pm`, but `pm 6 pm` says it twice
+    let pm_match = match (cap.name("leading_pm"), cap.name("pm")) {
+        (Some(_), Some(pm)) => {
+            return Err(TimeParseError::InvalidFormat(
+                Field::Pm,
+                StringSection::new(s, pm.range()),
+            ))
+        }
+        (leading, trailing) => leading.or(trailing),
+    };
+
+    // hour could be 24-hour but there's still an am/pm
+
+    // three or four digits on their own, like `0900` or `2130`, are military time
+    let military = cap.name("hour").filter(|hour| {
+        (3..=4).contains(&hour.as_str().len())
+            && hour.as_str().bytes().all(|c| c.is_ascii_digit())
+            && cap.name("fraction").is_none()
+            && cap.name("minute").is_none()
+            && cap.name("oclock").is_none()
+            && pm_match.is_none()
+    });
+
+    // `6,30:15` is probably a typo, so don't guess which separator was meant
+    if let (Some(minute_sep), Some(second_sep)) = (cap.name("minute_sep"), cap.name("second_sep")) {
+        if minute_sep.as_str().trim() != second_sep.as_str().trim() {
+            return Err(TimeParseError::InvalidFormat(
+                Field::Second,
+                StringSection::new(s, separator_range(second_sep)),
+            ));
+        }
+    }
+
+    // `9:30 o'clock` doesn't make sense, o'clock means on the hour
+    if let Some(oclock) = cap.name("oclock") {
+        let start = match (cap.name("fraction"), cap.name("minute_sep")) {
+            // the decimal point comes right before the fraction
+            (Some(fraction), _) => Some(fraction.start() - 1),
+            (None, Some(minute_sep)) => Some(separator_range(minute_sep).start),
+            (None, None) => None,
+        };
+        if let Some(start) = start {
+            return Err(TimeParseError::InvalidFormat(
+                Field::Minute,
+                StringSection::new(s, start..oclock.end()),
+            ));
+        }
+    }
+
+    let hour = match (military, cap.name("hour")) {
+        (Some(digits), _) => {
+            parse_field_at(s, Field::Hour, 0..25, digits.start()..digits.end() - 2)?
+        }
+        (None, None) => {
+            return Err(TimeParseError::IncompleteField(
+                Field::Hour,
+                StringSection::new(s, 0..s.len()),
+            ))
+        }
+        // 24 is only allowed for `24:00`, which is checked once the minutes and seconds are known
+        (None, Some(capture)) => parse_field(s, Field::Hour, 0..25, capture)?,
+    };
+    let minute = match (military, cap.name("fraction"), cap.name("minute")) {
+        (Some(digits), _, _) => {
+            parse_field_at(s, Field::Minute, 0..60, digits.end() - 2..digits.end())?
+        }
+        (None, Some(fraction), _) => parse_fraction(s, fraction)?,
+        (None, None, None) => 0,
+        // the minutes were skipped over to get to the seconds, like `6::6`
+        (None, None, Some(capture))
+            if capture.as_str().is_empty() && cap.name("second").is_some() =>
+        {
+            0
+        }
+        (None, None, Some(capture)) => parse_field(s, Field::Minute, 0..60, capture)?,
+    };
+    let second = match cap.name("second") {
+        None => 0,
+        Some(capture) => parse_field(s, Field::Second, 0..60, capture)?,
+    };
+
+    // `24:30` is past the end of the day, so it's out of range like any other hour would be
+    if hour == 24 && (minute, second) != (0, 0) {
+        let digits = cap.name("hour").unwrap();
+        let digits = match military {
+            Some(_) => digits.start()..digits.end() - 2,
+            None => digits.range(),
+        };
+        return Err(TimeParseError::OutOfRange(
+            Field::Hour,
+            StringSection::new(s, digits),
+            hour.check_range(0..24).unwrap_err(),
+        ));
+    }
+    let end_of_day = hour == 24;
+
+    let mut pm = match pm_match {
+        None => None,
+        Some(pm) => Some(match pm.as_str() {
+            pm if is_pm(pm, "am") || is_pm(pm, "a") => {
+                if hour == 12 {
+                    // 12 am is midnight, so it's hour zero in 24-hour time
+                    Duration::hours(-12)
+                } else {
+                    Duration::zero()
+                }
+            }
+            pm if is_pm(pm, "pm") || is_pm(pm, "p") => {
+                if hour == 12 {
+                    // 12 pm is already correct
+                    // we don't need to do anything to convert to 24-hour time
+                    Duration::zero()
+                } else {
+                    Duration::hours(12)
+                }
+            }
+            _ => {
+                return Err(TimeParseError::InvalidFormat(
+                    Field::Pm,
+                    StringSection::new(s, pm.range()),
+                ))
+            }
+        }),
+    };
+
+    if hour > 12 && pm.is_some() {
+        // `18:30 pm` says the same thing twice, which is only wrong if we're being strict
+        if !(options.lenient && pm == Some(Duration::hours(12))) {
+            return Err(TimeParseError::Overconstrained {
+                hour: StringSection::new(s, cap.name("hour").unwrap().range()),
+                pm: StringSection::new(s, pm_match.unwrap().range()),
+            });
+        }
+        // the hour is already in 24-hour time
+        pm = None;
+    }
+
+    // `24:00` is at the end of the day, but it's the same time on the clock as `00:00`
+    let mut time = NaiveTime::from_hms_opt(hour % 24, minute, second).unwrap();
+
+    if let Some(diff) = pm {
+        time += diff;
+    }
+
+    if !with_spans {
+        return Ok(TimeOfDay {
+            time,
+            end_of_day,
+            spans: Vec::new(),
+        });
+    }
+    let mut spans = match military {
+        Some(digits) => vec![
+            (Field::Hour, digits.start()..digits.end() - 2),
+            (Field::Minute, digits.end() - 2..digits.end()),
+        ],
+        None => {
+            let minute = cap.name("fraction").or_else(|| cap.name("minute"));
+            [(Field::Hour, cap.name("hour")), (Field::Minute, minute)]
+                .into_iter()
+                .filter_map(|(field, capture)| Some((field, capture?.range())))
+                .collect()
+        }
+    };
+    spans.extend(
+        [(Field::Second, cap.name("second")), (Field::Pm, pm_match)]
+            .into_iter()
+            .filter_map(|(field, capture)| Some((field, capture?.range()))),
+    );
+    // the minutes skipped over in `6::6` aren't anywhere to be found
+    spans.retain(|(_, range)| !range.is_empty());
+    spans.sort_by_key(|(_, range)| range.start);
+
+    Ok(TimeOfDay {
+        time,
+        end_of_day,
+        spans,
+    })
+}
+
+/**
+ * Parse phrases like `half past 9`, `quarter past 6 pm`, or `quarter to 10`
+ *
+ * The hour can be anything `opinionated_time_parsing` understands, as long as it's on the hour.
+ * Returns `None` if `s` doesn't start with `half` or `quarter`, so it can be parsed normally
+ */
+fn parse_phrase(
+    s: &str,
+    options: ParseOptions,
+    with_spans: bool,
+) -> Option<Result<(NaiveTime, FieldSpans), TimeParseError>> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(?xi)
+            ^\s*
+            (?P<amount>half|quarter) # how far from the hour
+            (?:\s+(?P<relation>[a-z]+))? # past or to
+            (?:\s+(?P<hour>.*))? # the hour
+            $
+        "
+        )
+        .unwrap();
+    }
+
+    let cap = captures(&RE, s)?;
+    let amount = cap.name("amount").unwrap();
+    let relation = match cap.name("relation") {
+        Some(relation) => relation,
+        None => {
+            return Some(Err(TimeParseError::IncompleteField(
+                Field::Minute,
+                StringSection::new(s, amount.end()..s.len()),
+            )))
+        }
+    };
+
+    let is = |(amount_is, relation_is): (&str, &str)| {
+        amount.as_str().eq_ignore_ascii_case(amount_is)
+            && relation.as_str().eq_ignore_ascii_case(relation_is)
+    };
+    let minutes = match () {
+        _ if is(("half", "past")) => 30,
+        _ if is(("quarter", "past")) => 15,
+        _ if is(("quarter", "to")) => -15,
+        // `half to 9` could mean 8:30 or 9:30 depending on who you ask, so it's an error
+        _ => {
+            return Some(Err(TimeParseError::InvalidFormat(
+                Field::Minute,
+                StringSection::new(s, amount.start()..relation.end()),
+            )))
+        }
+    };
+
+    let hour = match cap.name("hour") {
+        Some(hour) => hour,
+        None => {
+            return Some(Err(TimeParseError::IncompleteField(
+                Field::Hour,
+                StringSection::new(s, relation.end()..s.len()),
+            )))
+        }
+    };
+    let parsed = match parse_time_of_day(hour.as_str(), options, with_spans) {
+        Ok(parsed) => parsed,
+        Err(err) => return Some(Err(err.shifted(s, hour.start()))),
+    };
+    let time = parsed.time;
+    // `quarter past 9:30` is too much information
+    if time.minute() != 0 || time.second() != 0 {
+        return Some(Err(TimeParseError::InvalidFormat(
+            Field::Hour,
+            StringSection::new(s, hour.range()),
+        )));
+    }
+    // `quarter to 24` is just before midnight, but anything past 24 is past the end of the day,
+    // like `24:30` is
+    if parsed.end_of_day && !relation.as_str().eq_ignore_ascii_case("to") {
+        let digits = hour.as_str().len()
+            - hour
+                .as_str()
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        return Some(Err(TimeParseError::OutOfRange(
+            Field::Hour,
+            StringSection::new(s, hour.start()..hour.start() + digits),
+            24.check_range(0..24).unwrap_err(),
+        )));
+    }
+
+    let spans = if with_spans {
+        std::iter::once((Field::Minute, amount.start()..relation.end()))
+            .chain(parsed.spans.into_iter().map(|(field, range)| {
+                (field, range.start + hour.start()..range.end + hour.start())
+            }))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Some(Ok((time + Duration::minutes(minutes), spans)))
+}
+
+/**
+ * Parse times that leave off the leading fields, like `:45` or `:30:00`, filling them in from
+ * `options.now`
+ *
+ * The result is the next time the clock reads that, so `:45` at 9:10:50 is 9:11:45, and
+ * `:30:00` at 9:40 is 10:30:00. Returns `None` if `s` doesn't start with a colon
+ */
+fn parse_partial(
+    s: &str,
+    options: ParseOptions,
+    with_spans: bool,
+) -> Option<Result<(NaiveTime, FieldSpans), TimeParseError>> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"(?x)
+            ^\s*
+            :(?P<first>-?\d*)          # the minute or the second (required)
+            (?::(?P<second>-?\d*))?    # the second, if the first was the minute (optional)
+            \s*$
+        "
+        )
+        .unwrap();
+    }
+
+    let cap = captures(&RE, s)?;
+    let first = cap.name("first").unwrap().range();
+    let spans = match cap.name("second") {
+        _ if !with_spans => Vec::new(),
+        Some(second) => vec![(Field::Minute, first), (Field::Second, second.range())],
+        None => vec![(Field::Second, first)],
+    };
+    Some(fill_partial(s, &cap, options.now).map(|time| (time, spans)))
+}
+
+/// Fill in the fields `parse_partial` found from `now`
+fn fill_partial(
+    s: &str,
+    cap: &Captures,
+    now: Option<NaiveTime>,
+) -> Result<NaiveTime, TimeParseError> {
+    let now = now
+        .ok_or_else(|| TimeParseError::IncompleteField(Field::Hour, StringSection::new(s, 0..0)))?;
+    let first = cap.name("first").unwrap();
+    let now = now.with_nanosecond(0).unwrap();
+
+    let (time, unit) = match cap.name("second") {
+        Some(second) => {
+            let minute = parse_field(s, Field::Minute, 0..60, first)?;
+            let second = parse_field(s, Field::Second, 0..60, second)?;
+            (
+                NaiveTime::from_hms(now.hour(), minute, second),
+                Duration::hours(1),
+            )
+        }
+        None => {
+            let second = parse_field(s, Field::Second, 0..60, first)?;
+            (
+                NaiveTime::from_hms(now.hour(), now.minute(), second),
+                Duration::minutes(1),
+            )
+        }
+    };
+
+    // it's already passed this hour (or minute), so it's the next one
+    if time < now {
+        Ok(time + unit)
+    } else {
+        Ok(time)
+    }
+}
+
+/// Like `re.captures(s)`, but without allocating room for the captures when `s` doesn't match
+/// at all, which is most of the time when trying one format after another
+fn captures<'a>(re: &Regex, s: &'a str) -> Option<Captures<'a>> {
+    if re.is_match(s) {
+        re.captures(s)
+    } else {
+        None
+    }
+}
+
+/// The range of just the separator itself in a separator with spaces around it, like ` : `
+fn separator_range(separator: Match) -> Range<usize> {
+    let start =
+        separator.start() + separator.as_str().len() - separator.as_str().trim_start().len();
+    start..start + 1
+}
+
+/// Turn the digits after the decimal point of a fractional hour into minutes
+fn parse_fraction(s: &str, capture: Match) -> Result<u32, TimeParseError> {
+    if capture.as_str().is_empty() {
+        return Err(TimeParseError::IncompleteField(
+            Field::Minute,
+            StringSection::new(s, capture.range()),
+        ));
+    }
+    let fraction: f64 = format!("0.{}", capture.as_str()).parse().unwrap();
+    // something like `6.999` would round up to the next hour, so stop at the last minute of this one
+    Ok(((fraction * 60.0).round() as u32).min(59))
+}
+
+/// Whether `pm` is `normalized` once it's been through `normalize_pm`, without allocating
+fn is_pm(pm: &str, normalized: &str) -> bool {
+    pm.chars()
+        .filter(|c| *c != '.' && !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .eq(normalized.chars())
+}
+
+/// Lowercase an am/pm and strip out any periods or spaces, so `P. M.` becomes `pm`
+fn normalize_pm(pm: &str) -> String {
+    pm.chars()
+        .filter(|c| *c != '.' && !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn parse_field(
+    s: &str,
+    field: Field,
+    range: Range<u32>,
+    capture: Match,
+) -> Result<u32, TimeParseError> {
+    parse_field_at(s, field, range, capture.range())
+}
+
+/// Like `parse_field`, but for any section of `s` rather than a whole capture
+fn parse_field_at(
+    s: &str,
+    field: Field,
+    range: Range<u32>,
+    section: Range<usize>,
+) -> Result<u32, TimeParseError> {
+    s[section.clone()]
+        .parse::<u32>()
+        .map_err(|err| match err.kind() {
+            IntErrorKind::Empty => {
+                TimeParseError::IncompleteField(field, StringSection::new(s, section.clone()))
+            }
+            IntErrorKind::InvalidDigit => {
+                TimeParseError::InvalidFormat(field, StringSection::new(s, section.clone()))
+            }
+            IntErrorKind::PosOverflow => {
+                TimeParseError::TooLarge(field, StringSection::new(s, section.clone()))
+            }
+            _ => TimeParseError::InvalidFormat(field, StringSection::new(s, section.clone())),
+        })?
+        .check_range(range)
+        .map_err(|err| TimeParseError::OutOfRange(field, StringSection::new(s, section), err))
+}
+
+/// The number of single character insertions, deletions, or substitutions to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[test]
+fn time_parsing_happy_paths() {
+    assert_eq!(
+        opinionated_time_parsing("6").unwrap(),
+        NaiveTime::from_hms(6, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6am").unwrap(),
+        NaiveTime::from_hms(6, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6pm").unwrap(),
+        NaiveTime::from_hms(18, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6 pm").unwrap(),
+        NaiveTime::from_hms(18, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6:30").unwrap(),
+        NaiveTime::from_hms(6, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6:30pm").unwrap(),
+        NaiveTime::from_hms(18, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6:30 pm").unwrap(),
+        NaiveTime::from_hms(18, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6:30:15").unwrap(),
+        NaiveTime::from_hms(6, 30, 15)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6:30:15 pm").unwrap(),
+        NaiveTime::from_hms(18, 30, 15)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6::6").unwrap(),
+        NaiveTime::from_hms(6, 0, 6)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6::6 pm").unwrap(),
+        NaiveTime::from_hms(18, 0, 6)
+    );
+    assert_eq!(
+        opinionated_time_parsing(" 6 pm ").unwrap(),
+        NaiveTime::from_hms(18, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9 : 30").unwrap(),
+        NaiveTime::from_hms(9, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9 : 30 : 15 pm").unwrap(),
+        NaiveTime::from_hms(21, 30, 15)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6,30").unwrap(),
+        NaiveTime::from_hms(6, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6,30,15").unwrap(),
+        NaiveTime::from_hms(6, 30, 15)
+    );
+    assert_eq!(
+        opinionated_time_parsing("0900").unwrap(),
+        NaiveTime::from_hms(9, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("900").unwrap(),
+        NaiveTime::from_hms(9, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("2130").unwrap(),
+        NaiveTime::from_hms(21, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("09").unwrap(),
+        NaiveTime::from_hms(9, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6.5").unwrap(),
+        NaiveTime::from_hms(6, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6.25").unwrap(),
+        NaiveTime::from_hms(6, 15, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6.5 pm").unwrap(),
+        NaiveTime::from_hms(18, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("12am").unwrap(),
+        NaiveTime::from_hms(0, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("12:30am").unwrap(),
+        NaiveTime::from_hms(0, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("12:00:01am").unwrap(),
+        NaiveTime::from_hms(0, 0, 1)
+    );
+    assert_eq!(
+        opinionated_time_parsing("12pm").unwrap(),
+        NaiveTime::from_hms(12, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6 a.m.").unwrap(),
+        NaiveTime::from_hms(6, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6P.M.").unwrap(),
+        NaiveTime::from_hms(18, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("6 p. m.").unwrap(),
+        NaiveTime::from_hms(18, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9 o'clock").unwrap(),
+        NaiveTime::from_hms(9, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9oclock").unwrap(),
+        NaiveTime::from_hms(9, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9 O'Clock pm").unwrap(),
+        NaiveTime::from_hms(21, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9a").unwrap(),
+        NaiveTime::from_hms(9, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9p").unwrap(),
+        NaiveTime::from_hms(21, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9:30a").unwrap(),
+        NaiveTime::from_hms(9, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("9:30 P").unwrap(),
+        NaiveTime::from_hms(21, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("half past 9").unwrap(),
+        NaiveTime::from_hms(9, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("quarter to 10").unwrap(),
+        NaiveTime::from_hms(9, 45, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("Quarter past 6 pm").unwrap(),
+        NaiveTime::from_hms(18, 15, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("quarter to midnight").unwrap(),
+        NaiveTime::from_hms(23, 45, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("noon").unwrap(),
+        NaiveTime::from_hms(12, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("Midnight").unwrap(),
+        NaiveTime::from_hms(0, 0, 0)
+    );
+}
+
+#[test]
+fn format_examples_parse() {
+    for (pattern, example) in FORMATS {
+        assert!(
+            opinionated_time_parsing(example).is_ok(),
+            "the example for `{}` doesn't parse",
+            pattern
+        );
+    }
+}
+
+#[test]
+fn partial_time_parsing() {
+    let options = ParseOptions {
+        now: Some(NaiveTime::from_hms(9, 10, 30)),
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        opinionated_time_parsing_with(":45", options).unwrap(),
+        NaiveTime::from_hms(9, 10, 45)
+    );
+    assert_eq!(
+        opinionated_time_parsing_with(":15", options).unwrap(),
+        NaiveTime::from_hms(9, 11, 15)
+    );
+    assert_eq!(
+        opinionated_time_parsing_with(":30:00", options).unwrap(),
+        NaiveTime::from_hms(9, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing_with(":05:00", options).unwrap(),
+        NaiveTime::from_hms(10, 5, 0)
+    );
+    // the next minute after 23:59 is in the next day
+    assert_eq!(
+        opinionated_time_parsing_with(
+            ":10",
+            ParseOptions {
+                now: Some(NaiveTime::from_hms(23, 59, 50)),
+                ..ParseOptions::default()
+            }
+        )
+        .unwrap(),
+        NaiveTime::from_hms(0, 0, 10)
+    );
+    let err = opinionated_time_parsing_with(":60", options).expect_err("seconds are out of bounds");
+    match &err {
+        TimeParseError::OutOfRange(Field::Second, section, _) => assert_eq!(section.range(), 1..3),
+        _ => panic!("expected an out of range error, got {:?}", err),
+    }
+    println!("{}", err);
+    assert!(matches!(
+        opinionated_time_parsing(":45"),
+        Err(TimeParseError::IncompleteField(Field::Hour, _))
+    ));
+}
+
+#[test]
+fn leading_pm_parsing() {
+    assert_eq!(
+        opinionated_time_parsing("pm 6").unwrap(),
+        NaiveTime::from_hms(18, 0, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("am 9:30").unwrap(),
+        NaiveTime::from_hms(9, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing("P.M. 12").unwrap(),
+        NaiveTime::from_hms(12, 0, 0)
+    );
+
+    let err = opinionated_time_parsing("pm 18").expect_err("18 is already 24-hour");
+    assert_eq!(
+        err,
+        TimeParseError::Overconstrained {
+            hour: StringSection::new("pm 18", 3..5),
+            pm: StringSection::new("pm 18", 0..2),
+        }
+    );
+    match &err {
+        TimeParseError::Overconstrained { hour, pm } => assert_eq!(
+            overconstrained_note(hour, pm),
+            "18 is already in the evening, so drop the \"pm\" or write 6pm"
+        ),
+        _ => unreachable!(),
+    }
+    println!("{}", err);
+
+    assert_eq!(
+        opinionated_time_parsing("pm 6 pm").unwrap_err(),
+        TimeParseError::InvalidFormat(Field::Pm, StringSection::new("pm 6 pm", 5..7))
+    );
+    // the keywords aren't numbers, so there's nothing for the am/pm to go with
+    assert_eq!(
+        opinionated_time_parsing("pm noon").unwrap_err().kind(),
+        "invalid_format"
+    );
+}
+
+#[test]
+fn field_spans() {
+    assert_eq!(
+        parse_with_spans("6:30 pm"),
+        Ok((
+            NaiveTime::from_hms(18, 30, 0),
+            vec![
+                (Field::Hour, 0..1),
+                (Field::Minute, 2..4),
+                (Field::Pm, 5..7)
+            ]
+        ))
+    );
+    let spans = |s| parse_with_spans(s).unwrap().1;
+    assert_eq!(
+        spans("pm 6:30:15"),
+        vec![
+            (Field::Pm, 0..2),
+            (Field::Hour, 3..4),
+            (Field::Minute, 5..7),
+            (Field::Second, 8..10)
+        ]
+    );
+    assert_eq!(
+        spans("2130"),
+        vec![(Field::Hour, 0..2), (Field::Minute, 2..4)]
+    );
+    assert_eq!(
+        spans("6::15"),
+        vec![(Field::Hour, 0..1), (Field::Second, 3..5)]
+    );
+    assert_eq!(spans("noon"), vec![(Field::Keyword, 0..4)]);
+    assert_eq!(
+        spans("quarter past 6 pm"),
+        vec![
+            (Field::Minute, 0..12),
+            (Field::Hour, 13..14),
+            (Field::Pm, 15..17)
+        ]
+    );
+    assert!(parse_with_spans("6:61").is_err());
+}
+
+#[test]
+fn lenient_time_parsing() {
+    let lenient = ParseOptions {
+        lenient: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        opinionated_time_parsing_with("18:30 pm", lenient).unwrap(),
+        NaiveTime::from_hms(18, 30, 0)
+    );
+    assert_eq!(
+        opinionated_time_parsing_with("6:30 pm", lenient).unwrap(),
+        NaiveTime::from_hms(18, 30, 0)
+    );
+    assert!(matches!(
+        opinionated_time_parsing_with("18:30 am", lenient),
+        Err(TimeParseError::Overconstrained { .. })
+    ));
+    assert!(matches!(
+        opinionated_time_parsing("18:30 pm"),
+        Err(TimeParseError::Overconstrained { .. })
+    ));
+}
+
+#[test]
+fn time_parsing_edge_cases() {
+    println!(
+        "{}",
+        opinionated_time_parsing("").expect_err("test string is empty")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("18:30 pm").expect_err("test string is overconstrained")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("6:306").expect_err("minutes are out of bounds")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("6:").expect_err("minutes are missing")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("6:0:").expect_err("seconds are missing")
+    );
+    let err = opinionated_time_parsing("6.").expect_err("fraction is missing");
+    assert!(matches!(
+        err,
+        TimeParseError::IncompleteField(Field::Minute, _)
+    ));
+    println!("{}", err);
+    println!(
+        "{}",
+        opinionated_time_parsing("20:-30").expect_err("negative numbers are not allowed")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("63").expect_err("63 hours is greater than maximum of 23 hours")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("6555")
+            .expect_err("6555 hours is greater than maximum of 23 hours")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("hello").expect_err("`hello` is not a time")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("6 xm").expect_err("`xm` is not am or pm")
+    );
+    let err = opinionated_time_parsing("0970").expect_err("military minutes are out of bounds");
+    match &err {
+        TimeParseError::OutOfRange(Field::Minute, section, _) => assert_eq!(section.range(), 2..4),
+        _ => panic!("expected an out of range error, got {:?}", err),
+    }
+    println!("{}", err);
+    let err = opinionated_time_parsing("9pm junk").expect_err("`junk` isn't part of the time");
+    match &err {
+        TimeParseError::InvalidFormat(Field::Overall, section) => assert_eq!(section.range(), 4..8),
+        _ => panic!("expected an invalid format error, got {:?}", err),
+    }
+    println!("{}", err);
+    let err = opinionated_time_parsing("9 : 70").expect_err("minutes are out of bounds");
+    match &err {
+        TimeParseError::OutOfRange(Field::Minute, section, _) => assert_eq!(section.range(), 4..6),
+        _ => panic!("expected an out of range error, got {:?}", err),
+    }
+    println!("{}", err);
+    let err = opinionated_time_parsing("9 : 30 : 75").expect_err("seconds are out of bounds");
+    match &err {
+        TimeParseError::OutOfRange(Field::Second, section, _) => assert_eq!(section.range(), 9..11),
+        _ => panic!("expected an out of range error, got {:?}", err),
+    }
+    println!("{}", err);
+    let err = opinionated_time_parsing("6,30 : 15").expect_err("separators are mixed");
+    match &err {
+        TimeParseError::InvalidFormat(Field::Second, section) => assert_eq!(section.range(), 5..6),
+        _ => panic!("expected an invalid format error, got {:?}", err),
+    }
+    let err = opinionated_time_parsing("6,30:15").expect_err("separators are mixed");
+    match &err {
+        TimeParseError::InvalidFormat(Field::Second, section) => assert_eq!(section.range(), 4..5),
+        _ => panic!("expected an invalid format error, got {:?}", err),
+    }
+    println!("{}", err);
+    let err = opinionated_time_parsing("9x").expect_err("`x` is not am or pm");
+    match &err {
+        TimeParseError::InvalidFormat(Field::Pm, section) => assert_eq!(section.range(), 1..2),
+        _ => panic!("expected an invalid format error, got {:?}", err),
+    }
+    println!("{}", err);
+    let err = opinionated_time_parsing("half to 9").expect_err("`half to` is ambiguous");
+    match &err {
+        TimeParseError::InvalidFormat(Field::Minute, section) => assert_eq!(section.range(), 0..7),
+        _ => panic!("expected an invalid format error, got {:?}", err),
+    }
+    println!("{}", err);
+    let err = opinionated_time_parsing("quarter past 25").expect_err("hour is out of bounds");
+    match &err {
+        TimeParseError::OutOfRange(Field::Hour, section, _) => assert_eq!(section.range(), 13..15),
+        _ => panic!("expected an out of range error, got {:?}", err),
+    }
+    // 24 is only the midnight at the end of the day, so nothing can come after it
+    assert_eq!(
+        opinionated_time_parsing("half past 24"),
+        Err(TimeParseError::OutOfRange(
+            Field::Hour,
+            StringSection::new("half past 24", 10..12),
+            24.check_range(0..24).unwrap_err()
+        ))
+    );
+    assert_eq!(
+        opinionated_time_parsing("quarter to 24").unwrap(),
+        NaiveTime::from_hms(23, 45, 0)
+    );
+    println!("{}", err);
+    println!(
+        "{}",
+        opinionated_time_parsing("half past 9:30").expect_err("the hour has minutes")
+    );
+    println!(
+        "{}",
+        opinionated_time_parsing("half past").expect_err("the hour is missing")
+    );
+    let err = opinionated_time_parsing("9:30 o'clock").expect_err("o'clock is on the hour");
+    match &err {
+        TimeParseError::InvalidFormat(Field::Minute, section) => assert_eq!(section.range(), 1..12),
+        _ => panic!("expected an invalid format error, got {:?}", err),
+    }
+    println!("{}", err);
+    println!(
+        "{}",
+        opinionated_time_parsing("noom").expect_err("`noom` is not a keyword")
+    );
+}
+
+#[test]
+fn duration_parsing_happy_paths() {
+    assert_eq!(
+        parse_target("30m").unwrap(),
+        ParsedTime::Relative(Duration::minutes(30))
+    );
+    assert_eq!(
+        parse_target("90s").unwrap(),
+        ParsedTime::Relative(Duration::seconds(90))
+    );
+    assert_eq!(
+        parse_target("1h30m").unwrap(),
+        ParsedTime::Relative(Duration::minutes(90))
+    );
+    assert_eq!(
+        parse_target("1h30m15s").unwrap(),
+        ParsedTime::Relative(Duration::seconds(5415))
+    );
+    assert_eq!(
+        parse_target("6:30pm").unwrap(),
+        ParsedTime::Absolute(NaiveTime::from_hms(18, 30, 0))
+    );
+}
+
+#[test]
+fn error_inspection() {
+    let err = opinionated_time_parsing("6:306").unwrap_err();
+    assert_eq!(err.field(), Field::Minute);
+    assert_eq!(err.kind(), "out_of_range");
+
+    let err = opinionated_time_parsing("6:0:").unwrap_err();
+    assert_eq!(err.field(), Field::Second);
+    assert_eq!(err.kind(), "incomplete");
+
+    let err = opinionated_time_parsing("hello").unwrap_err();
+    assert_eq!(err.field(), Field::Overall);
+    assert_eq!(err.kind(), "invalid_format");
+
+    let err = opinionated_time_parsing("18:30 pm").unwrap_err();
+    assert_eq!(err.field(), Field::Pm);
+    assert_eq!(err.kind(), "overconstrained");
+}
+
+#[test]
+fn overconstrained_suggestion() {
+    let note = |s: &str| match opinionated_time_parsing(s).unwrap_err() {
+        TimeParseError::Overconstrained { hour, pm } => overconstrained_note(&hour, &pm),
+        err => panic!("expected an overconstrained error, got {:?}", err),
+    };
+    assert_eq!(
+        note("13pm"),
+        "13 is already in the afternoon, so drop the \"pm\" or write 1pm"
+    );
+    assert_eq!(
+        note("18:30 am"),
+        "18:30 is already in the evening, so drop the \"am\" or write 6:30pm"
+    );
+    assert_eq!(
+        note("24:00 p.m."),
+        "24:00 is already midnight, so drop the \"p.m.\""
+    );
+    println!("{}", opinionated_time_parsing("18:30 pm").unwrap_err());
+}
+
+#[test]
+fn pm_typo_suggestion() {
+    let err = opinionated_time_parsing("6 pn").unwrap_err();
+    assert_eq!(err.field(), Field::Pm);
+    assert!(err.to_string().contains("did you mean 'pm'?"));
+    assert!(opinionated_time_parsing("6 om")
+        .unwrap_err()
+        .to_string()
+        .contains("did you mean 'am' or 'pm'?"));
+    assert!(opinionated_time_parsing("6:30 p.n.")
+        .unwrap_err()
+        .to_string()
+        .contains("did you mean 'pm'?"));
+    // too far off to guess
+    assert!(!opinionated_time_parsing("6 xyz")
+        .unwrap_err()
+        .to_string()
+        .contains("did you mean"));
+}
+
+#[test]
+fn error_comparison() {
+    assert_eq!(
+        opinionated_time_parsing("6:0:").unwrap_err(),
+        TimeParseError::IncompleteField(Field::Second, StringSection::new("6:0:", 4..4))
+    );
+    assert_eq!(
+        opinionated_time_parsing("18:30 pm").unwrap_err(),
+        TimeParseError::Overconstrained {
+            hour: StringSection::new("18:30 pm", 0..2),
+            pm: StringSection::new("18:30 pm", 6..8),
+        }
+    );
+
+    let err = opinionated_time_parsing("6:306").unwrap_err();
+    assert_eq!(err.clone(), err);
+    assert_ne!(err, opinionated_time_parsing("6:307").unwrap_err());
+}
+
+#[test]
+fn huge_number_parsing() {
+    let huge = "99999999999999999999";
+    for (input, field, range) in [
+        (huge.to_string(), Field::Hour, 0..20),
+        (format!("9:{}", huge), Field::Minute, 2..22),
+        (format!("{}m", huge), Field::Minute, 0..20),
+        (format!("@{}", huge), Field::Timestamp, 1..21),
+    ] {
+        let err = parse_target(&input).expect_err("the number is too large");
+        assert_eq!(
+            err,
+            TimeParseError::TooLarge(field, StringSection::new(&input, range))
+        );
+        assert_eq!(err.kind(), "too_large");
+    }
+    println!("{}", parse_target(huge).unwrap_err());
+
+    // the regex crate never backtracks, so long inputs fail as quickly as short ones
+    let long = "9".repeat(10_000);
+    assert_eq!(parse_target(&long).unwrap_err().kind(), "too_large");
+    let long = format!("9:30 {}", "a.m ".repeat(2_500));
+    assert_eq!(parse_target(&long).unwrap_err().kind(), "invalid_format");
+    assert_eq!(
+        parse_zoned_targets(&long).unwrap_err().kind(),
+        "invalid_format"
+    );
+}
+
+#[test]
+fn date_parsing() {
+    assert_eq!(
+        parse_target("2024-12-25 9am").unwrap(),
+        ParsedTime::DateTime(NaiveDate::from_ymd(2024, 12, 25).and_hms(9, 0, 0))
+    );
+    assert_eq!(
+        parse_target("2024-12-25 21:30").unwrap(),
+        ParsedTime::DateTime(NaiveDate::from_ymd(2024, 12, 25).and_hms(21, 30, 0))
+    );
+    assert_eq!(
+        parse_target("2024-1-5").unwrap(),
+        ParsedTime::DateTime(NaiveDate::from_ymd(2024, 1, 5).and_hms(0, 0, 0))
+    );
+    println!(
         "{}",
-        opinionated_time_parsing("6:0:").expect_err("seconds are missing")
+        parse_target("2024-02-30 9am").expect_err("february 30th doesn't exist")
     );
     println!(
         "{}",
-        opinionated_time_parsing("20:-30").expect_err("negative numbers are not allowed")
+        parse_target("2024-12-25 9:70").expect_err("minutes are out of bounds")
+    );
+}
+
+#[test]
+fn date_field_errors() {
+    let err = parse_target("2023-02-29 9am").unwrap_err();
+    assert_eq!(
+        err,
+        TimeParseError::OutOfRange(
+            Field::Day,
+            StringSection::new("2023-02-29 9am", 8..10),
+            29.check_range(1..29).unwrap_err()
+        )
+    );
+    assert!(err.report(false).contains("day field is out of range"));
+    assert!(err.report(false).contains("(1..29) for day"));
+
+    let err = parse_target("2024-13-01").unwrap_err();
+    assert_eq!(err.field(), Field::Month);
+    assert!(err.report(false).contains("month field is out of range"));
+
+    let err = parse_target("Decembr 25").unwrap_err();
+    assert_eq!(
+        err,
+        TimeParseError::InvalidFormat(Field::Month, StringSection::new("Decembr 25", 0..7))
+    );
+    assert!(err.report(false).contains("month has invalid format"));
+    assert_eq!(parse_target("31 Apr").unwrap_err().field(), Field::Day);
+
+    let err = parse_target("999999-01-01").unwrap_err();
+    assert_eq!(err.field(), Field::Year);
+    assert!(err.report(false).contains("year field is out of range"));
+    // the very end of what chrono can represent is out of range, rather than overflowing later
+    for s in ["262143-12-31 24:00", "262143-12-31 23:59"] {
+        assert_eq!(
+            parse_target(s),
+            Err(TimeParseError::OutOfRange(
+                Field::Year,
+                StringSection::new(s, 0..6),
+                262143.check_range(0..10000).unwrap_err()
+            ))
+        );
+    }
+    assert_eq!(
+        parse_target("9999-12-31 24:00"),
+        Ok(ParsedTime::DateTime(
+            NaiveDate::from_ymd(10000, 1, 1).and_hms(0, 0, 0)
+        ))
+    );
+    assert_eq!(
+        parse_target("99999999999-01-01").unwrap_err().kind(),
+        "too_large"
+    );
+    assert_eq!(
+        parse_relative_duration("P99999999999D").unwrap_err(),
+        TimeParseError::TooLarge(Field::Day, StringSection::new("P99999999999D", 1..12))
+    );
+}
+
+#[test]
+fn plus_duration_parsing() {
+    assert_eq!(
+        parse_target("+10"),
+        Ok(ParsedTime::Relative(Duration::minutes(10)))
+    );
+    assert_eq!(
+        parse_target("+1h30m"),
+        Ok(ParsedTime::Relative(Duration::minutes(90)))
+    );
+    assert_eq!(
+        parse_target("+PT45S"),
+        Ok(ParsedTime::Relative(Duration::seconds(45)))
+    );
+    assert_eq!(
+        parse_target("+"),
+        Err(TimeParseError::IncompleteField(
+            Field::Minute,
+            StringSection::new("+", 1..1)
+        ))
+    );
+    assert_eq!(
+        parse_target("+99999999999999999999").unwrap_err().kind(),
+        "too_large"
+    );
+    assert_eq!(parse_target("+9am").unwrap_err().kind(), "invalid_format");
+    // without the `+`, it's still a time of day
+    assert_eq!(
+        parse_target("10"),
+        Ok(ParsedTime::Absolute(NaiveTime::from_hms(10, 0, 0)))
+    );
+}
+
+#[test]
+fn month_name_parsing() {
+    let nine = NaiveTime::from_hms(9, 0, 0);
+    let midnight = NaiveTime::from_hms(0, 0, 0);
+    assert_eq!(
+        parse_target("Dec 25 9am"),
+        Ok(ParsedTime::OnDate(12, 25, nine))
+    );
+    assert_eq!(
+        parse_target("25 Dec"),
+        Ok(ParsedTime::OnDate(12, 25, midnight))
+    );
+    assert_eq!(
+        parse_target("25th DECEMBER 9am"),
+        Ok(ParsedTime::OnDate(12, 25, nine))
+    );
+    assert_eq!(
+        parse_target("sep. 3 21:30"),
+        Ok(ParsedTime::OnDate(9, 3, NaiveTime::from_hms(21, 30, 0)))
+    );
+    assert_eq!(
+        parse_target("Feb 29"),
+        Ok(ParsedTime::OnDate(2, 29, midnight))
+    );
+    // the midnight at the end of the day is the start of the next one
+    assert_eq!(
+        parse_target("Dec 31 24:00"),
+        Ok(ParsedTime::OnDate(1, 1, midnight))
+    );
+
+    assert_eq!(parse_target("Feb 30 9am").unwrap_err().field(), Field::Day);
+    assert_eq!(
+        parse_target("Decmber 25").unwrap_err().field(),
+        Field::Month
+    );
+    assert_eq!(
+        parse_target("Dec 25 9:75").unwrap_err().field(),
+        Field::Minute
+    );
+    // still times, not dates
+    assert_eq!(
+        parse_target("6 pm"),
+        Ok(ParsedTime::Absolute(NaiveTime::from_hms(18, 0, 0)))
+    );
+    assert_eq!(
+        parse_target("pm 6"),
+        Ok(ParsedTime::Absolute(NaiveTime::from_hms(18, 0, 0)))
+    );
+}
+
+#[test]
+fn timestamp_parsing() {
+    assert_eq!(
+        parse_target("@1700000000").unwrap(),
+        ParsedTime::Instant(NaiveDate::from_ymd(2023, 11, 14).and_hms(22, 13, 20))
+    );
+    assert_eq!(
+        parse_target("@0").unwrap(),
+        ParsedTime::Instant(NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 0))
+    );
+    let err = parse_target("@abc").expect_err("`abc` isn't a number");
+    match &err {
+        TimeParseError::InvalidFormat(Field::Timestamp, section) => {
+            assert_eq!(section.range(), 1..4)
+        }
+        _ => panic!("expected an invalid format error, got {:?}", err),
+    }
+    println!("{}", err);
+    assert!(matches!(
+        parse_target("@9223372036854775807"),
+        Err(TimeParseError::InvalidFormat(Field::Timestamp, _))
+    ));
+}
+
+#[test]
+fn day_keyword_parsing() {
+    assert_eq!(
+        parse_target("tomorrow 9am").unwrap(),
+        ParsedTime::OnDay(1, NaiveTime::from_hms(9, 0, 0))
+    );
+    assert_eq!(
+        parse_target("Today 6:30 pm").unwrap(),
+        ParsedTime::OnDay(0, NaiveTime::from_hms(18, 30, 0))
+    );
+    assert_eq!(
+        parse_target("tomorrow").unwrap(),
+        ParsedTime::OnDay(1, NaiveTime::from_hms(0, 0, 0))
+    );
+    let err = parse_target("tomorrow 9:70").expect_err("minutes are out of bounds");
+    match &err {
+        TimeParseError::OutOfRange(Field::Minute, section, _) => {
+            assert_eq!(section.range(), 11..13)
+        }
+        _ => panic!("expected an out of range error, got {:?}", err),
+    }
+    println!("{}", err);
+}
+
+#[test]
+fn big_number_minutes() {
+    let options = ParseOptions {
+        minutes_if_big: true,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        parse_target_with("9", options).unwrap(),
+        ParsedTime::Absolute(NaiveTime::from_hms(9, 0, 0))
+    );
+    assert_eq!(
+        parse_target_with("90", options).unwrap(),
+        ParsedTime::Relative(Duration::minutes(90))
+    );
+    assert_eq!(
+        parse_target_with("0900", options).unwrap(),
+        ParsedTime::Absolute(NaiveTime::from_hms(9, 0, 0))
+    );
+    assert_eq!(
+        parse_target_with("24", options).unwrap(),
+        ParsedTime::OnDay(1, NaiveTime::from_hms(0, 0, 0))
+    );
+    assert_eq!(
+        parse_target_with("10000", options).unwrap(),
+        ParsedTime::Relative(Duration::minutes(10000))
+    );
+    // only a number on its own
+    assert_eq!(
+        parse_target_with("90pm", options).unwrap_err().kind(),
+        "out_of_range"
+    );
+    // and not without asking
+    assert_eq!(parse_target("90").unwrap_err().kind(), "out_of_range");
+}
+
+#[test]
+fn midnight_spellings() {
+    let midnight = NaiveTime::from_hms(0, 0, 0);
+    for s in [
+        "0", "00", "0:00", "00:00:00", "0000", "000", "12am", "12 a.m.", "midnight",
+    ] {
+        assert_eq!(opinionated_time_parsing(s), Ok(midnight), "{}", s);
+    }
+    // the start of the day is a time of day like any other, unlike the end of it
+    assert_eq!(parse_target("0"), Ok(ParsedTime::Absolute(midnight)));
+    assert_eq!(parse_target("00"), Ok(ParsedTime::Absolute(midnight)));
+    assert_eq!(parse_target("24"), Ok(ParsedTime::OnDay(1, midnight)));
+    assert_eq!(
+        opinionated_time_parsing("0 pm"),
+        Ok(NaiveTime::from_hms(12, 0, 0))
+    );
+}
+
+#[test]
+fn end_of_day_parsing() {
+    let midnight = NaiveTime::from_hms(0, 0, 0);
+    assert_eq!(opinionated_time_parsing("24:00").unwrap(), midnight);
+    assert_eq!(opinionated_time_parsing("2400").unwrap(), midnight);
+    assert_eq!(
+        parse_target("24:00").unwrap(),
+        ParsedTime::OnDay(1, midnight)
+    );
+    assert_eq!(parse_target("24").unwrap(), ParsedTime::OnDay(1, midnight));
+    assert_eq!(
+        parse_target("tomorrow 24:00:00").unwrap(),
+        ParsedTime::OnDay(2, midnight)
+    );
+    assert_eq!(
+        parse_target("2024-12-31 24:00").unwrap(),
+        ParsedTime::DateTime(NaiveDate::from_ymd(2025, 1, 1).and_time(midnight))
+    );
+
+    for (input, range) in [
+        ("24:01", 0..2),
+        ("24:00:30", 0..2),
+        ("2430", 0..2),
+        ("25:00", 0..2),
+    ] {
+        let err = opinionated_time_parsing(input).expect_err("past the end of the day");
+        match &err {
+            TimeParseError::OutOfRange(Field::Hour, section, _) => {
+                assert_eq!(section.range(), range, "{}", input)
+            }
+            _ => panic!(
+                "expected an out of range error for {}, got {:?}",
+                input, err
+            ),
+        }
+    }
+    println!("{}", opinionated_time_parsing("24:30").unwrap_err());
+}
+
+#[test]
+fn zone_parsing() {
+    assert_eq!(
+        parse_zoned_target("14:00 UTC").unwrap(),
+        (
+            ParsedTime::Absolute(NaiveTime::from_hms(14, 0, 0)),
+            Some(Zone::Fixed(FixedOffset::east(0)))
+        )
+    );
+    assert_eq!(
+        parse_zoned_target("9am +05:30").unwrap(),
+        (
+            ParsedTime::Absolute(NaiveTime::from_hms(9, 0, 0)),
+            Some(Zone::Fixed(FixedOffset::east(5 * 60 * 60 + 30 * 60)))
+        )
+    );
+    assert_eq!(
+        parse_zoned_target("9 pm GMT-0800").unwrap(),
+        (
+            ParsedTime::Absolute(NaiveTime::from_hms(21, 0, 0)),
+            Some(Zone::Fixed(FixedOffset::west(8 * 60 * 60)))
+        )
     );
+    assert_eq!(
+        parse_zoned_target("9 pm").unwrap(),
+        (ParsedTime::Absolute(NaiveTime::from_hms(21, 0, 0)), None)
+    );
+    #[cfg(feature = "tz")]
+    {
+        assert_eq!(
+            parse_zoned_target("9am EST").unwrap(),
+            (
+                ParsedTime::Absolute(NaiveTime::from_hms(9, 0, 0)),
+                Some(Zone::Named(chrono_tz::America::New_York))
+            )
+        );
+        assert_eq!(
+            parse_zoned_target("2024-12-25 9am Europe/London").unwrap(),
+            (
+                ParsedTime::DateTime(NaiveDate::from_ymd(2024, 12, 25).and_hms(9, 0, 0)),
+                Some(Zone::Named(chrono_tz::Europe::London))
+            )
+        );
+    }
     println!(
         "{}",
-        opinionated_time_parsing("63").expect_err("63 hours is greater than maximum of 23 hours")
+        parse_zoned_target("9am +25:00").expect_err("offset is out of bounds")
     );
     println!(
         "{}",
-        opinionated_time_parsing("6555")
-            .expect_err("6555 hours is greater than maximum of 23 hours")
+        parse_zoned_target("9:70 UTC").expect_err("minutes are out of bounds")
+    );
+}
+
+#[test]
+fn multiple_target_parsing() {
+    assert_eq!(
+        parse_zoned_targets("9am 12pm 3pm").unwrap(),
+        vec![
+            (ParsedTime::Absolute(NaiveTime::from_hms(9, 0, 0)), None),
+            (ParsedTime::Absolute(NaiveTime::from_hms(12, 0, 0)), None),
+            (ParsedTime::Absolute(NaiveTime::from_hms(15, 0, 0)), None),
+        ]
+    );
+    assert_eq!(
+        parse_zoned_targets("30m,1h").unwrap(),
+        vec![
+            (ParsedTime::Relative(Duration::minutes(30)), None),
+            (ParsedTime::Relative(Duration::hours(1)), None),
+        ]
+    );
+    assert_eq!(
+        parse_zoned_targets("9am,30m").unwrap(),
+        vec![
+            (ParsedTime::Absolute(NaiveTime::from_hms(9, 0, 0)), None),
+            (ParsedTime::Relative(Duration::minutes(30)), None),
+        ]
+    );
+    // a comma between two numbers separates the hour and minute, not two times
+    assert_eq!(
+        parse_zoned_targets("6,30").unwrap(),
+        vec![(ParsedTime::Absolute(NaiveTime::from_hms(6, 30, 0)), None)]
+    );
+    assert_eq!(
+        parse_zoned_targets("9:30 pm").unwrap(),
+        vec![(ParsedTime::Absolute(NaiveTime::from_hms(21, 30, 0)), None)]
     );
     println!(
         "{}",
-        opinionated_time_parsing("hello").expect_err("`hello` is not a time")
+        parse_zoned_targets("9am 12pm 3:70pm").expect_err("minutes are out of bounds")
+    );
+    assert!(matches!(
+        parse_zoned_targets("18:30 pm"),
+        Err(TimeParseError::Overconstrained { .. })
+    ));
+}
+
+#[test]
+fn iso_duration_parsing() {
+    assert_eq!(
+        parse_target("PT1H").unwrap(),
+        ParsedTime::Relative(Duration::hours(1))
+    );
+    assert_eq!(
+        parse_target("PT90M").unwrap(),
+        ParsedTime::Relative(Duration::minutes(90))
+    );
+    assert_eq!(
+        parse_target("PT1H30M15S").unwrap(),
+        ParsedTime::Relative(Duration::seconds(5415))
+    );
+    assert_eq!(
+        parse_target("P1DT12H").unwrap(),
+        ParsedTime::Relative(Duration::hours(36))
+    );
+    let err = parse_target("PT").expect_err("there's nothing after the T");
+    assert!(matches!(
+        err,
+        TimeParseError::IncompleteField(Field::Hour, _)
+    ));
+    println!("{}", err);
+    println!(
+        "{}",
+        parse_target("PT1M1H").expect_err("units are out of order")
+    );
+}
+
+#[test]
+fn duration_parsing_edge_cases() {
+    println!(
+        "{}",
+        parse_target("1h70m").expect_err("minutes are out of bounds after hours")
+    );
+    println!("{}", parse_target("1hm").expect_err("minutes are missing"));
+    println!(
+        "{}",
+        parse_target("30m1h").expect_err("units are out of order")
+    );
+    println!("{}", parse_target("").expect_err("test string is empty"));
+}
+
+#[test]
+fn pm_matching() {
+    assert!(is_pm("pm", "pm"));
+    assert!(is_pm("P. M.", "pm"));
+    assert!(is_pm("a.m.", "am"));
+    assert!(!is_pm("pm", "p"));
+    assert!(!is_pm("am", "pm"));
+    // the spans are only worked out when they're asked for
+    assert!(parse_time_of_day("9:30 pm", ParseOptions::default(), false)
+        .unwrap()
+        .spans
+        .is_empty());
+}
+
+#[test]
+fn weekday_parsing() {
+    let nine = NaiveTime::from_hms(9, 0, 0);
+    let midnight = NaiveTime::from_hms(0, 0, 0);
+    assert_eq!(
+        parse_target("monday 9am"),
+        Ok(ParsedTime::OnWeekday(Weekday::Mon, nine))
+    );
+    assert_eq!(
+        parse_target("Fri 5:30pm"),
+        Ok(ParsedTime::OnWeekday(
+            Weekday::Fri,
+            NaiveTime::from_hms(17, 30, 0)
+        ))
+    );
+    assert_eq!(
+        parse_target("SUNDAY"),
+        Ok(ParsedTime::OnWeekday(Weekday::Sun, midnight))
+    );
+    assert_eq!(
+        parse_target("wed. 9"),
+        Ok(ParsedTime::OnWeekday(Weekday::Wed, nine))
+    );
+    // the midnight at the end of Sunday is the start of Monday
+    assert_eq!(
+        parse_target("sun 24:00"),
+        Ok(ParsedTime::OnWeekday(Weekday::Mon, midnight))
+    );
+
+    let err = parse_target("wednsday 9am").unwrap_err();
+    assert_eq!(
+        err,
+        TimeParseError::InvalidFormat(Field::Weekday, StringSection::new("wednsday 9am", 0..8))
+    );
+    assert_eq!(parse_target("tue 9:75").unwrap_err().field(), Field::Minute);
+    // still times, and the months aren't days
+    assert_eq!(
+        parse_target("noon"),
+        Ok(ParsedTime::Absolute(NaiveTime::from_hms(12, 0, 0)))
+    );
+    assert_eq!(
+        parse_target("jun 5"),
+        Ok(ParsedTime::OnDate(6, 5, midnight))
+    );
+}
+
+#[test]
+fn blank_input() {
+    for s in ["", "   ", "\t"] {
+        assert_eq!(
+            opinionated_time_parsing(s),
+            Err(TimeParseError::IncompleteField(
+                Field::Overall,
+                StringSection::new(s, 0..s.len())
+            )),
+            "{:?}",
+            s
+        );
+        assert_eq!(parse_target(s).unwrap_err().kind(), "incomplete");
+    }
+    // whitespace around a time is still fine, and errors point into the original string
+    assert_eq!(
+        opinionated_time_parsing(" noon\t"),
+        Ok(NaiveTime::from_hms(12, 0, 0))
+    );
+    match opinionated_time_parsing("  9:75 ") {
+        Err(TimeParseError::OutOfRange(Field::Minute, section, _)) => {
+            assert_eq!(section, StringSection::new("  9:75 ", 4..6))
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(
+        parse_with_spans(" 9pm").unwrap().1,
+        vec![(Field::Hour, 1..2), (Field::Pm, 2..4)]
+    );
+}
+
+#[test]
+fn negative_fields() {
+    for (s, field, range) in [
+        ("-30", Field::Hour, 0..3),
+        ("-0", Field::Hour, 0..2),
+        ("20:-30", Field::Minute, 3..6),
+        ("-30m", Field::Minute, 0..3),
+    ] {
+        let err = parse_target(s).unwrap_err();
+        assert_eq!(
+            err,
+            TimeParseError::InvalidFormat(field, StringSection::new(s, range)),
+            "{}",
+            s
+        );
+    }
+    let note = |s| {
+        let report = parse_target(s).unwrap_err().to_string();
+        report.contains("did you mean a duration?")
+    };
+    assert!(note("-30"));
+    assert!(note("-0"));
+    assert!(note("20:-30"));
+    let report = parse_target("-30m").unwrap_err().to_string();
+    assert!(report.contains("durations can't be negative"));
+}
+
+#[test]
+fn incomplete_without_color() {
+    let report = opinionated_time_parsing("9:").unwrap_err().report(false);
+    assert!(report.contains("minute field is incomplete"));
+    assert!(!report.contains('\x1b'), "{:?}", report);
+    let report = opinionated_time_parsing("9:").unwrap_err().report(true);
+    assert!(report.contains('\x1b'), "{:?}", report);
+}
+
+#[test]
+fn split_errors() {
+    // the lone `:` isn't a target of its own, so the error is about `9 :` as a whole
+    let err = parse_zoned_targets("9 :").unwrap_err();
+    assert_eq!(
+        err,
+        TimeParseError::IncompleteField(Field::Minute, StringSection::new("9 :", 3..3))
+    );
+    assert_eq!(parse_zoned_targets("9am , 10am").unwrap().len(), 2);
+    // every part looks like a target, so the one that's wrong is pointed out
+    assert_eq!(
+        parse_zoned_targets("9am 9:75").unwrap_err(),
+        parse_target("9:75").unwrap_err().shifted("9am 9:75", 4)
     );
 }